@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::config::deserialize_duration;
+use crate::report::Report;
+
+/// Contents of a rules file: a list of `[[rule]]` tables.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<Rule>,
+}
+
+/// A threshold on one of the values the report computes.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub name: String,
+    pub metric: Metric,
+    /// Restricts disk, inode and disk I/O rules to one mount point; every
+    /// disk is checked otherwise.
+    pub mount_point: Option<String>,
+    pub op: Op,
+    pub threshold: f64,
+    #[serde(default)]
+    pub severity: Severity,
+    /// How long the condition has to hold before the rule fires.
+    #[serde(default, rename = "for", deserialize_with = "deserialize_duration")]
+    pub for_duration: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    CpuUsagePercent,
+    CpuIowaitPercent,
+    CpuStealPercent,
+    MemoryUsedPercent,
+    MemoryAvailablePercent,
+    SwapUsedPercent,
+    SwapInPagesPerSecond,
+    SwapOutPagesPerSecond,
+    DiskUsedPercent,
+    InodesUsedPercent,
+    DiskHoursUntilFull,
+    DiskIoUtilizationPercent,
+    RaidDegradedDevices,
+    Load1,
+    Load5,
+    Load15,
+    LoadPerCore,
+    CpuPressureSome,
+    MemoryPressureSome,
+    MemoryPressureFull,
+    IoPressureSome,
+    IoPressureFull,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Op {
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    #[default]
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    /// The condition holds but not yet for the rule's `for` duration.
+    Pending,
+    Firing,
+}
+
+/// A rule whose condition currently holds.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Alert {
+    pub rule: String,
+    pub severity: Severity,
+    pub state: AlertState,
+    pub metric: Metric,
+    /// Mount point, device, array or pressure scope the value belongs to.
+    pub instance: Option<String>,
+    pub value: f64,
+    pub op: Op,
+    pub threshold: f64,
+    pub since: DateTime<Local>,
+}
+
+#[derive(Debug)]
+pub enum RulesError {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::Read(path, err) => write!(f, "cannot read rules file {}: {}", path.display(), err),
+            RulesError::Parse(path, err) => write!(f, "invalid rules file {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+pub fn load_rules(path: &Path) -> Result<Vec<Rule>, RulesError> {
+    let contents = std::fs::read_to_string(path).map_err(|err| RulesError::Read(path.to_path_buf(), err))?;
+    let file: RulesFile = toml::from_str(&contents).map_err(|err| RulesError::Parse(path.to_path_buf(), err))?;
+    Ok(file.rules)
+}
+
+/// Evaluates rules against successive reports, remembering since when each
+/// condition has held so `for` durations work across samples.
+pub struct AlertEngine {
+    rules: Vec<Rule>,
+    active_since: HashMap<(usize, Option<String>), DateTime<Local>>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        AlertEngine {
+            rules,
+            active_since: HashMap::new(),
+        }
+    }
+
+    /// Returns every pending or firing alert, most severe first.
+    pub fn evaluate(&mut self, report: &Report) -> Vec<Alert> {
+        let now = report.generated_at;
+        let mut alerts = Vec::new();
+        let mut still_active = HashMap::new();
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            for (instance, value) in metric_values(rule, report) {
+                if !rule.op.holds(value, rule.threshold) {
+                    continue;
+                }
+
+                let key = (index, instance.clone());
+                let since = self.active_since.get(&key).copied().unwrap_or(now);
+                still_active.insert(key, since);
+
+                let held_for = (now - since).to_std().unwrap_or_default();
+                let state = match rule.for_duration {
+                    Some(for_duration) if held_for < for_duration => AlertState::Pending,
+                    _ => AlertState::Firing,
+                };
+
+                alerts.push(Alert {
+                    rule: rule.name.clone(),
+                    severity: rule.severity,
+                    state,
+                    metric: rule.metric,
+                    instance,
+                    value,
+                    op: rule.op,
+                    threshold: rule.threshold,
+                    since,
+                });
+            }
+        }
+
+        // Conditions that stopped holding start over next time.
+        self.active_since = still_active;
+
+        alerts.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.rule.cmp(&b.rule)));
+        alerts
+    }
+}
+
+impl Op {
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Op::Gt => value > threshold,
+            Op::Ge => value >= threshold,
+            Op::Lt => value < threshold,
+            Op::Le => value <= threshold,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+        }
+    }
+}
+
+impl Metric {
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::CpuUsagePercent => "cpu_usage_percent",
+            Metric::CpuIowaitPercent => "cpu_iowait_percent",
+            Metric::CpuStealPercent => "cpu_steal_percent",
+            Metric::MemoryUsedPercent => "memory_used_percent",
+            Metric::MemoryAvailablePercent => "memory_available_percent",
+            Metric::SwapUsedPercent => "swap_used_percent",
+            Metric::SwapInPagesPerSecond => "swap_in_pages_per_second",
+            Metric::SwapOutPagesPerSecond => "swap_out_pages_per_second",
+            Metric::DiskUsedPercent => "disk_used_percent",
+            Metric::InodesUsedPercent => "inodes_used_percent",
+            Metric::DiskHoursUntilFull => "disk_hours_until_full",
+            Metric::DiskIoUtilizationPercent => "disk_io_utilization_percent",
+            Metric::RaidDegradedDevices => "raid_degraded_devices",
+            Metric::Load1 => "load1",
+            Metric::Load5 => "load5",
+            Metric::Load15 => "load15",
+            Metric::LoadPerCore => "load_per_core",
+            Metric::CpuPressureSome => "cpu_pressure_some",
+            Metric::MemoryPressureSome => "memory_pressure_some",
+            Metric::MemoryPressureFull => "memory_pressure_full",
+            Metric::IoPressureSome => "io_pressure_some",
+            Metric::IoPressureFull => "io_pressure_full",
+        }
+    }
+}
+
+impl AlertState {
+    pub fn label(self) -> &'static str {
+        match self {
+            AlertState::Pending => "pending",
+            AlertState::Firing => "firing",
+        }
+    }
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "INFO",
+            Severity::Warning => "WARNING",
+            Severity::Critical => "CRITICAL",
+        }
+    }
+}
+
+/// Values of the rule's metric in the report, with the mount point for disks,
+/// the device for disk I/O, the array for RAID and the scope (`system` or a
+/// cgroup path) for pressure.
+/// Empty when the section holding the metric was not collected.
+fn metric_values(rule: &Rule, report: &Report) -> Vec<(Option<String>, f64)> {
+    let times = report.cpu.as_ref().and_then(|cpu| cpu.times);
+    let memory = report.memory.as_ref();
+    let system = report.system.as_ref();
+    let value = match rule.metric {
+        Metric::CpuUsagePercent => report.cpu.as_ref().map(|cpu| cpu.usage_percent as f64),
+        Metric::CpuIowaitPercent => times.map(|times| times.iowait),
+        Metric::CpuStealPercent => times.map(|times| times.steal),
+        Metric::MemoryUsedPercent => memory.map(|memory| memory.used_percent),
+        Metric::MemoryAvailablePercent => memory.map(|memory| memory.available_percent),
+        // No swap configured means none of it is used.
+        Metric::SwapUsedPercent => memory.map(|memory| memory.swap.as_ref().map_or(0.0, |swap| swap.used_percent)),
+        Metric::SwapInPagesPerSecond => memory
+            .and_then(|memory| memory.swap.as_ref().map_or(Some(0.0), |swap| swap.swap_in_pages_per_second)),
+        Metric::SwapOutPagesPerSecond => memory
+            .and_then(|memory| memory.swap.as_ref().map_or(Some(0.0), |swap| swap.swap_out_pages_per_second)),
+        Metric::Load1 => system.map(|system| system.load_average.one),
+        Metric::Load5 => system.map(|system| system.load_average.five),
+        Metric::Load15 => system.map(|system| system.load_average.fifteen),
+        Metric::LoadPerCore => system.map(|system| system.load_per_core),
+        Metric::DiskUsedPercent => {
+            return report
+                .disks
+                .iter()
+                .flatten()
+                .filter(|disk| rule.mount_point.as_ref().is_none_or(|mount| *mount == disk.mount_point))
+                .map(|disk| (Some(disk.mount_point.clone()), disk.used_percent))
+                .collect();
+        }
+        Metric::InodesUsedPercent => {
+            return report
+                .disks
+                .iter()
+                .flatten()
+                .filter(|disk| rule.mount_point.as_ref().is_none_or(|mount| *mount == disk.mount_point))
+                .filter_map(|disk| Some((Some(disk.mount_point.clone()), disk.inodes.as_ref()?.used_percent)))
+                .collect();
+        }
+        // Only growing filesystems have a time until full.
+        Metric::DiskHoursUntilFull => {
+            return report
+                .disks
+                .iter()
+                .flatten()
+                .filter(|disk| rule.mount_point.as_ref().is_none_or(|mount| *mount == disk.mount_point))
+                .filter_map(|disk| {
+                    let seconds = disk.forecast?.seconds_until_full?;
+                    Some((Some(disk.mount_point.clone()), seconds as f64 / 3600.0))
+                })
+                .collect();
+        }
+        Metric::DiskIoUtilizationPercent => {
+            return report
+                .disk_io
+                .iter()
+                .flatten()
+                .filter(|device| rule.mount_point.as_ref().is_none_or(|mount| device.mount_points.contains(mount)))
+                .filter_map(|device| Some((Some(device.device.clone()), device.rates?.utilization_percent)))
+                .collect();
+        }
+        Metric::RaidDegradedDevices => {
+            // An inactive array counts as degraded even without a count.
+            return report
+                .raid
+                .iter()
+                .flatten()
+                .map(|array| (Some(array.name.clone()), array.degraded_devices.max(array.is_degraded() as u64) as f64))
+                .collect();
+        }
+        Metric::CpuPressureSome
+        | Metric::MemoryPressureSome
+        | Metric::MemoryPressureFull
+        | Metric::IoPressureSome
+        | Metric::IoPressureFull => {
+            let Some(pressure) = &report.pressure else {
+                return Vec::new();
+            };
+            return pressure
+                .scopes()
+                .filter_map(|(scope, resources)| {
+                    let stall = match rule.metric {
+                        Metric::CpuPressureSome => resources.cpu?.some,
+                        Metric::MemoryPressureSome => resources.memory?.some,
+                        Metric::MemoryPressureFull => resources.memory?.full?,
+                        Metric::IoPressureSome => resources.io?.some,
+                        _ => resources.io?.full?,
+                    };
+                    Some((Some(scope.to_string()), stall.avg10))
+                })
+                .collect();
+        }
+    };
+    value.map(|value| (None, value)).into_iter().collect()
+}
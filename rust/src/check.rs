@@ -0,0 +1,273 @@
+use clap::Args;
+
+use server_stats::alert::{AlertEngine, AlertState, Severity};
+use server_stats::collect::Sampler;
+use server_stats::config::{CheckConfig, Config};
+use server_stats::report::{Report, Section};
+
+/// Warning/critical thresholds for `check`. Values at or above a threshold
+/// trigger it. Unset flags fall back to the `[check]` table of the
+/// configuration file, then to the defaults.
+#[derive(Debug, Clone, Args)]
+pub struct CheckArgs {
+    /// CPU usage % that triggers WARNING [default: 90]
+    #[arg(long)]
+    pub cpu_warning: Option<f64>,
+    /// CPU usage % that triggers CRITICAL [default: 95]
+    #[arg(long)]
+    pub cpu_critical: Option<f64>,
+    /// Used memory % that triggers WARNING [default: 90]
+    #[arg(long)]
+    pub memory_warning: Option<f64>,
+    /// Used memory % that triggers CRITICAL [default: 95]
+    #[arg(long)]
+    pub memory_critical: Option<f64>,
+    /// Used swap % that triggers WARNING [default: 50]
+    #[arg(long)]
+    pub swap_warning: Option<f64>,
+    /// Used swap % that triggers CRITICAL [default: 80]
+    #[arg(long)]
+    pub swap_critical: Option<f64>,
+    /// Used disk % of any mount point that triggers WARNING [default: 85]
+    #[arg(long)]
+    pub disk_warning: Option<f64>,
+    /// Used disk % of any mount point that triggers CRITICAL [default: 95]
+    #[arg(long)]
+    pub disk_critical: Option<f64>,
+    /// 1 minute load per core that triggers WARNING [default: 1.5]
+    #[arg(long)]
+    pub load_warning: Option<f64>,
+    /// 1 minute load per core that triggers CRITICAL [default: 2]
+    #[arg(long)]
+    pub load_critical: Option<f64>,
+}
+
+/// Thresholds after applying the command line and the configuration.
+#[derive(Debug, Clone)]
+struct Thresholds {
+    cpu_warning: f64,
+    cpu_critical: f64,
+    memory_warning: f64,
+    memory_critical: f64,
+    swap_warning: f64,
+    swap_critical: f64,
+    disk_warning: f64,
+    disk_critical: f64,
+    load_warning: f64,
+    load_critical: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            cpu_warning: 90.0,
+            cpu_critical: 95.0,
+            memory_warning: 90.0,
+            memory_critical: 95.0,
+            swap_warning: 50.0,
+            swap_critical: 80.0,
+            disk_warning: 85.0,
+            disk_critical: 95.0,
+            load_warning: 1.5,
+            load_critical: 2.0,
+        }
+    }
+}
+
+impl Thresholds {
+    fn resolve(args: &CheckArgs, config: &CheckConfig) -> Self {
+        let defaults = Thresholds::default();
+        Thresholds {
+            cpu_warning: args.cpu_warning.or(config.cpu_warning).unwrap_or(defaults.cpu_warning),
+            cpu_critical: args.cpu_critical.or(config.cpu_critical).unwrap_or(defaults.cpu_critical),
+            memory_warning: args.memory_warning.or(config.memory_warning).unwrap_or(defaults.memory_warning),
+            memory_critical: args.memory_critical.or(config.memory_critical).unwrap_or(defaults.memory_critical),
+            swap_warning: args.swap_warning.or(config.swap_warning).unwrap_or(defaults.swap_warning),
+            swap_critical: args.swap_critical.or(config.swap_critical).unwrap_or(defaults.swap_critical),
+            disk_warning: args.disk_warning.or(config.disk_warning).unwrap_or(defaults.disk_warning),
+            disk_critical: args.disk_critical.or(config.disk_critical).unwrap_or(defaults.disk_critical),
+            load_warning: args.load_warning.or(config.load_warning).unwrap_or(defaults.load_warning),
+            load_critical: args.load_critical.or(config.load_critical).unwrap_or(defaults.load_critical),
+        }
+    }
+}
+
+/// Nagios plugin states; the discriminant is the plugin exit code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    #[default]
+    Ok = 0,
+    Warning = 1,
+    Critical = 2,
+    Unknown = 3,
+}
+
+impl Status {
+    pub fn label(self) -> &'static str {
+        match self {
+            Status::Ok => "OK",
+            Status::Warning => "WARNING",
+            Status::Critical => "CRITICAL",
+            Status::Unknown => "UNKNOWN",
+        }
+    }
+
+    pub fn exit_code(self) -> i32 {
+        self as i32
+    }
+}
+
+/// Prints a single Nagios status line with perfdata and returns the status.
+pub fn run(args: &CheckArgs, config: &Config, alerts: Option<AlertEngine>) -> Status {
+    let thresholds = Thresholds::resolve(args, &config.check);
+    if let Some(message) = invalid_thresholds(&thresholds) {
+        println!("SERVER-STATS UNKNOWN - {}", message);
+        return Status::Unknown;
+    }
+
+    let mut report = Sampler::from_config(config)
+        .with_sections(&[Section::Cpu, Section::Memory, Section::Disk, Section::System, Section::Pressure, Section::Raid])
+        .sample();
+    if let Some(mut engine) = alerts {
+        report.alerts = Some(engine.evaluate(&report));
+    }
+
+    let (status, line) = evaluate(&thresholds, &report);
+    println!("{}", line);
+    status
+}
+
+fn invalid_thresholds(args: &Thresholds) -> Option<String> {
+    let pairs = [
+        ("cpu", args.cpu_warning, args.cpu_critical),
+        ("memory", args.memory_warning, args.memory_critical),
+        ("swap", args.swap_warning, args.swap_critical),
+        ("disk", args.disk_warning, args.disk_critical),
+        ("load", args.load_warning, args.load_critical),
+    ];
+    pairs
+        .iter()
+        .find(|(_, warning, critical)| warning > critical)
+        .map(|(name, warning, critical)| {
+            format!("{} warning threshold {} is above the critical threshold {}", name, warning, critical)
+        })
+}
+
+fn evaluate(args: &Thresholds, report: &Report) -> (Status, String) {
+    let (Some(cpu), Some(memory), Some(disks), Some(system)) =
+        (&report.cpu, &report.memory, &report.disks, &report.system)
+    else {
+        return (Status::Unknown, "SERVER-STATS UNKNOWN - stats could not be collected".to_string());
+    };
+
+    let mut check = Check::default();
+
+    check.threshold("cpu", cpu.usage_percent as f64, args.cpu_warning, args.cpu_critical, "%");
+    check.threshold("memory", memory.used_percent, args.memory_warning, args.memory_critical, "%");
+    let swap_percent = memory.swap.as_ref().map_or(0.0, |swap| swap.used_percent);
+    check.threshold("swap", swap_percent, args.swap_warning, args.swap_critical, "%");
+    check.threshold("load", system.load_per_core, args.load_warning, args.load_critical, "");
+
+    for disk in disks {
+        check.threshold(
+            &format!("disk {}", disk.mount_point),
+            disk.used_percent,
+            args.disk_warning,
+            args.disk_critical,
+            "%",
+        );
+    }
+
+    // A degraded md array is one disk failure away from data loss, whatever
+    // the thresholds say.
+    for array in report.raid.iter().flatten() {
+        let failed: Vec<_> = array.failed_members().map(|member| member.device.as_str()).collect();
+        if array.state == "inactive" {
+            check.status = check.status.max(Status::Critical);
+            check.problems.push(format!("raid {} inactive", array.name));
+        } else if array.is_degraded() {
+            check.status = check.status.max(Status::Critical);
+            let mut problem = format!("raid {} degraded", array.name);
+            if let (Some(raid_devices), Some(working)) = (array.raid_devices, array.working_devices) {
+                problem.push_str(&format!(" [{}/{}]", raid_devices, working));
+            }
+            if let Some(sync) = &array.sync {
+                problem.push_str(&format!(" ({} {:.1}%)", sync.action, sync.progress_percent.unwrap_or(0.0)));
+            }
+            check.problems.push(problem);
+        } else if !failed.is_empty() {
+            // A spare took over; the failed disk still has to be replaced.
+            check.status = check.status.max(Status::Warning);
+            check.problems.push(format!("raid {} failed member {}", array.name, failed.join(" ")));
+        }
+        check.perfdata.push(format!("'raid {}'={};;1;0", array.name, array.degraded_devices));
+    }
+
+    let load = &system.load_average;
+    check.perfdata.push(format!("load1={:.2};;;0", load.one));
+    check.perfdata.push(format!("load5={:.2};;;0", load.five));
+    check.perfdata.push(format!("load15={:.2};;;0", load.fifteen));
+
+    // Firing alert rules raise the status like any other threshold.
+    for alert in report.alerts.iter().flatten().filter(|alert| alert.state == AlertState::Firing) {
+        let status = match alert.severity {
+            Severity::Critical => Status::Critical,
+            Severity::Warning => Status::Warning,
+            Severity::Info => continue,
+        };
+        check.status = check.status.max(status);
+        match &alert.instance {
+            Some(instance) => check.problems.push(format!("rule {} firing on {}", alert.rule, instance)),
+            None => check.problems.push(format!("rule {} firing", alert.rule)),
+        }
+    }
+
+    let summary = if check.problems.is_empty() {
+        "all checks within thresholds".to_string()
+    } else {
+        check.problems.join(", ")
+    };
+    let line = format!(
+        "SERVER-STATS {} - {} | {}",
+        check.status.label(),
+        summary,
+        check.perfdata.join(" ")
+    );
+    (check.status, line)
+}
+
+#[derive(Default)]
+struct Check {
+    status: Status,
+    problems: Vec<String>,
+    perfdata: Vec<String>,
+}
+
+impl Check {
+    fn threshold(&mut self, name: &str, value: f64, warning: f64, critical: f64, unit: &str) {
+        let status = if value >= critical {
+            Status::Critical
+        } else if value >= warning {
+            Status::Warning
+        } else {
+            Status::Ok
+        };
+
+        if status != Status::Ok {
+            let limit = if status == Status::Critical { critical } else { warning };
+            self.problems.push(format!("{} {:.2}{} >= {}{}", name, value, unit, limit, unit));
+        }
+        self.status = self.status.max(status);
+
+        let max = if unit == "%" { "100" } else { "" };
+        self.perfdata.push(format!(
+            "'{}'={:.2}{};{};{};0;{}",
+            name.replace('\'', "''"),
+            value,
+            unit,
+            warning,
+            critical,
+            max
+        ));
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+
+use crate::check::CheckArgs;
+use server_stats::config::Config;
+use server_stats::render::Format;
+use server_stats::report::Section;
+
+/// Prints a snapshot of CPU, memory, disk, process, network and user stats.
+///
+/// Without a subcommand the report is printed once, like `server-stats report`.
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(flatten)]
+    pub report: ReportArgs,
+
+    /// Read settings from this TOML file after the system-wide and per-user ones
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Evaluate the alert rules in this TOML file against the collected stats
+    #[arg(long, global = true, value_name = "PATH")]
+    pub rules: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    /// The subcommand named in `args`, for when parsing them failed: the
+    /// first argument after the program name that is neither an option nor
+    /// the value of one, so `--only check` does not count.
+    pub fn subcommand_name(args: &[String]) -> Option<&str> {
+        let command = Cli::command();
+        let takes_value = |name: &str| {
+            command
+                .get_arguments()
+                .any(|arg| arg.get_long() == Some(name) && arg.get_action().takes_values())
+        };
+
+        let mut args = args.iter().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                return args.next().map(String::as_str);
+            }
+            match arg.strip_prefix("--") {
+                Some(name) if !name.contains('=') && takes_value(name) => {
+                    args.next();
+                }
+                Some(_) => {}
+                None if arg.starts_with('-') => {}
+                None => return Some(arg),
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the report once (the default)
+    Report(ReportArgs),
+    /// Redraw the report every interval, showing changes since the previous sample
+    Watch {
+        #[command(flatten)]
+        report: ReportArgs,
+
+        /// Time between samples, e.g. `5s`, `1m`
+        #[arg(long, default_value = "5s", value_parser = humantime::parse_duration)]
+        interval: Duration,
+    },
+    /// Nagios/Icinga plugin: print one status line with perfdata and exit 0/1/2/3
+    Check(CheckArgs),
+    /// Run an HTTP server exposing the stats as Prometheus metrics on /metrics
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "0.0.0.0:9101")]
+        listen: String,
+    },
+    /// Full-screen interactive dashboard
+    #[command(visible_alias = "tui")]
+    Top {
+        /// Time between refreshes, e.g. `2s`
+        #[arg(long, default_value = "2s", value_parser = humantime::parse_duration)]
+        interval: Duration,
+    },
+}
+
+/// Options shared by `report` and `watch`. Unset options fall back to the
+/// configuration file.
+#[derive(Debug, Clone, Args)]
+pub struct ReportArgs {
+    /// Output format [default: text]
+    #[arg(long, value_enum)]
+    pub format: Option<Format>,
+
+    /// Only include these sections (comma separated): cpu, memory, disk,
+    /// top-cpu, top-memory, system, network, ports, users, ssh, pressure,
+    /// block-devices, raid
+    #[arg(long, value_delimiter = ',', value_name = "SECTIONS", conflicts_with = "skip")]
+    pub only: Vec<Section>,
+
+    /// Leave out these sections (comma separated)
+    #[arg(long, value_delimiter = ',', value_name = "SECTIONS")]
+    pub skip: Vec<Section>,
+
+    /// Number of processes in the top CPU / memory lists [default: 5]
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub top: Option<usize>,
+}
+
+impl ReportArgs {
+    /// Sections selected by `--only`, or the configured ones minus `--skip`,
+    /// in report order.
+    pub fn sections(&self, config: &Config) -> Vec<Section> {
+        let enabled = config.sections();
+        Section::ALL
+            .into_iter()
+            .filter(|section| {
+                if self.only.is_empty() {
+                    enabled.contains(section)
+                } else {
+                    self.only.contains(section)
+                }
+            })
+            .filter(|section| !self.skip.contains(section))
+            .collect()
+    }
+
+    pub fn format(&self, config: &Config) -> Format {
+        self.format.unwrap_or_else(|| config.format())
+    }
+
+    pub fn top(&self, config: &Config) -> usize {
+        self.top.unwrap_or_else(|| config.top())
+    }
+}
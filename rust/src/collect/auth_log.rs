@@ -0,0 +1,485 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeZone, Utc};
+use flate2::read::MultiGzDecoder;
+
+use super::{Collector, window_start};
+use crate::report::{SshActivity, SshSummary};
+
+/// Debian/Ubuntu and RHEL/Fedora names of the authentication log.
+const DEFAULT_LOGS: [&str; 2] = ["/var/log/auth.log", "/var/log/secure"];
+const DEFAULT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventKind {
+    Accepted,
+    Failed,
+    InvalidUser,
+    /// The `Invalid user` line opening a connection for a missing user name,
+    /// see [`count_invalid_user_connections`].
+    InvalidUserConnection,
+    Disconnected,
+}
+
+#[derive(Debug, Clone)]
+struct SshEvent {
+    time: DateTime<Utc>,
+    kind: EventKind,
+    user: Option<String>,
+    source: Option<String>,
+    /// Source port, which ties the lines of one connection together.
+    port: Option<String>,
+    /// More than 1 for `message repeated N times` lines.
+    count: usize,
+}
+
+/// sshd logins, failures and disconnects from the authentication log,
+/// including rotated and gzipped copies, within a time window.
+pub struct SshLogCollector {
+    logs: Vec<PathBuf>,
+    window: Duration,
+    /// Events per file, with the modification time they were read at.
+    /// Rotated files never change, so they are only parsed once.
+    cache: HashMap<PathBuf, (SystemTime, Vec<SshEvent>)>,
+    errors: Vec<String>,
+}
+
+impl SshLogCollector {
+    pub fn new() -> Self {
+        SshLogCollector {
+            logs: DEFAULT_LOGS.iter().map(PathBuf::from).collect(),
+            window: DEFAULT_WINDOW,
+            cache: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Logs to scan; rotations next to them (`auth.log.1`, `auth.log.2.gz`,
+    /// `secure-20240101`) are found automatically.
+    pub fn with_logs(mut self, logs: Vec<PathBuf>) -> Self {
+        self.logs = logs;
+        self
+    }
+
+    /// Only events within this long before the sample are summarised.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Every existing log file and its rotations whose last change falls
+    /// within the window.
+    fn files(&self, since: SystemTime) -> Vec<(PathBuf, SystemTime)> {
+        let mut files = Vec::new();
+        for log in &self.logs {
+            let (Some(dir), Some(name)) = (log.parent(), log.file_name().and_then(|name| name.to_str())) else {
+                continue;
+            };
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let Some(file_name) = file_name.to_str() else {
+                    continue;
+                };
+                let rotation = file_name.strip_prefix(name);
+                if !matches!(rotation, Some(rest) if rest.is_empty() || rest.starts_with(['.', '-'])) {
+                    continue;
+                }
+                if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified())
+                    && modified >= since
+                {
+                    files.push((entry.path(), modified));
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+}
+
+impl Default for SshLogCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for SshLogCollector {
+    type Output = SshActivity;
+
+    fn refresh(&mut self) {
+        // Windows longer than the clock has run cover every file.
+        let since = SystemTime::now().checked_sub(self.window).unwrap_or(UNIX_EPOCH);
+        let files = self.files(since);
+
+        self.errors.clear();
+        self.cache.retain(|path, _| files.iter().any(|(file, _)| file == path));
+        for (path, modified) in files {
+            if self.cache.get(&path).is_some_and(|(cached, _)| *cached == modified) {
+                continue;
+            }
+            match read_events(&path, modified) {
+                Ok(events) => {
+                    self.cache.insert(path, (modified, events));
+                }
+                Err(err) => {
+                    self.cache.remove(&path);
+                    let hint = if err.kind() == io::ErrorKind::PermissionDenied {
+                        ", run as root or a member of the adm group"
+                    } else {
+                        ""
+                    };
+                    self.errors.push(format!("cannot read {}: {}{}", path.display(), err, hint));
+                }
+            }
+        }
+    }
+
+    fn collect(&self) -> SshActivity {
+        let since = window_start(self.window);
+        let mut files: Vec<_> = self.cache.keys().map(|path| path.display().to_string()).collect();
+        files.sort();
+
+        let mut activity = SshActivity {
+            window_seconds: self.window.as_secs(),
+            files,
+            errors: self.errors.clone(),
+            accepted: 0,
+            failed: 0,
+            invalid_users: 0,
+            disconnects: 0,
+            by_user: Vec::new(),
+            by_source: Vec::new(),
+        };
+        let mut users: HashMap<&str, SshSummary> = HashMap::new();
+        let mut sources: HashMap<&str, SshSummary> = HashMap::new();
+
+        let events = self.cache.values().flat_map(|(_, events)| events);
+        for event in events.filter(|event| event.time >= since) {
+            match event.kind {
+                EventKind::Accepted => activity.accepted += event.count,
+                EventKind::Failed => activity.failed += event.count,
+                EventKind::InvalidUser | EventKind::InvalidUserConnection => activity.invalid_users += event.count,
+                EventKind::Disconnected => {
+                    activity.disconnects += event.count;
+                    continue;
+                }
+            }
+            if let Some(user) = &event.user {
+                users.entry(user).or_insert_with(|| SshSummary::new(user)).add(event);
+            }
+            if let Some(source) = &event.source {
+                sources.entry(source).or_insert_with(|| SshSummary::new(source)).add(event);
+            }
+        }
+
+        activity.by_user = sorted(users);
+        activity.by_source = sorted(sources);
+        activity
+    }
+}
+
+impl SshSummary {
+    fn new(name: &str) -> Self {
+        SshSummary {
+            name: name.to_string(),
+            accepted: 0,
+            failed: 0,
+            invalid_user: 0,
+            last_seen: DateTime::<Utc>::MIN_UTC,
+        }
+    }
+
+    fn add(&mut self, event: &SshEvent) {
+        match event.kind {
+            EventKind::Accepted => self.accepted += event.count,
+            EventKind::Failed => self.failed += event.count,
+            EventKind::InvalidUser | EventKind::InvalidUserConnection => self.invalid_user += event.count,
+            EventKind::Disconnected => {}
+        }
+        self.last_seen = self.last_seen.max(event.time);
+    }
+}
+
+/// Most failures first, then most logins.
+fn sorted(summaries: HashMap<&str, SshSummary>) -> Vec<SshSummary> {
+    let mut summaries: Vec<_> = summaries.into_values().collect();
+    summaries.sort_by(|a, b| {
+        (b.failed + b.invalid_user, b.accepted, &a.name).cmp(&(a.failed + a.invalid_user, a.accepted, &b.name))
+    });
+    summaries
+}
+
+fn read_events(path: &Path, modified: SystemTime) -> io::Result<Vec<SshEvent>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|extension| extension == "gz") {
+        Box::new(MultiGzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+
+    // Classic syslog timestamps have no year; no entry is newer than the file.
+    let modified = DateTime::<Local>::from(modified);
+    let mut events = Vec::new();
+    for line in BufReader::new(reader).split(b'\n') {
+        let line = line?;
+        if let Some(event) = parse_line(&String::from_utf8_lossy(&line), modified) {
+            events.push(event);
+        }
+    }
+    count_invalid_user_connections(&mut events);
+    Ok(events)
+}
+
+/// sshd logs `Invalid user` when a connection for a missing user name
+/// starts, then `Failed ... for invalid user` for every password tried on
+/// it. The tries are the attempts; the connection line only counts when
+/// nothing was tried, e.g. a scanner that disconnects right away.
+fn count_invalid_user_connections(events: &mut Vec<SshEvent>) {
+    let tried: HashSet<_> = events
+        .iter()
+        .filter(|event| event.kind == EventKind::InvalidUser)
+        .map(|event| (event.source.clone(), event.port.clone()))
+        .collect();
+    events.retain_mut(|event| {
+        if event.kind != EventKind::InvalidUserConnection {
+            return true;
+        }
+        event.kind = EventKind::InvalidUser;
+        !tried.contains(&(event.source.clone(), event.port.clone()))
+    });
+}
+
+/// Parses `<timestamp> <host> sshd[pid]: <message>` lines; anything else is `None`.
+fn parse_line(line: &str, modified: DateTime<Local>) -> Option<SshEvent> {
+    let (time, rest) = parse_timestamp(line, modified)?;
+    let mut parts = rest.trim_start().splitn(3, ' ');
+    let _host = parts.next()?;
+    // OpenSSH 9.8 and later log from `sshd-session`.
+    if !parts.next()?.starts_with("sshd") {
+        return None;
+    }
+    let mut message = parts.next()?;
+
+    // rsyslog folds identical messages: `message repeated 3 times: [ Failed password ...]`.
+    let mut count = 1;
+    if let Some(rest) = message.strip_prefix("message repeated ") {
+        let (times, repeated) = rest.split_once(" times: [")?;
+        count = times.parse().ok()?;
+        message = repeated.trim_end().strip_suffix(']')?.trim();
+    }
+
+    let (kind, user, source, port) = if let Some(rest) = message.strip_prefix("Accepted ") {
+        let (user, source, port) = user_and_source(rest.split_once(" for ")?.1)?;
+        (EventKind::Accepted, Some(user), Some(source), port)
+    } else if let Some(rest) = message.strip_prefix("Failed ") {
+        let rest = rest.split_once(" for ")?.1;
+        let (kind, rest) = match rest.strip_prefix("invalid user ") {
+            Some(rest) => (EventKind::InvalidUser, rest),
+            None => (EventKind::Failed, rest),
+        };
+        let (user, source, port) = user_and_source(rest)?;
+        (kind, Some(user), Some(source), port)
+    } else if let Some(rest) = message.strip_prefix("Invalid user ") {
+        let (user, source, port) = user_and_source(rest)?;
+        (EventKind::InvalidUserConnection, Some(user), Some(source), port)
+    } else if let Some(rest) = message.strip_prefix("Disconnected from ") {
+        let (user, source) = disconnected(rest)?;
+        (EventKind::Disconnected, user, Some(source), None)
+    } else if let Some(rest) = message.strip_prefix("Received disconnect from ") {
+        (EventKind::Disconnected, None, Some(rest.split(' ').next()?.to_string()), None)
+    } else {
+        return None;
+    };
+
+    Some(SshEvent {
+        time: time.with_timezone(&Utc),
+        kind,
+        user: user.filter(|user| !user.is_empty()),
+        source,
+        port,
+        count,
+    })
+}
+
+/// RFC 3339 (`2024-05-01T12:00:00.123456+02:00`) or classic syslog
+/// (`May  1 12:00:00`) timestamps.
+fn parse_timestamp(line: &str, modified: DateTime<Local>) -> Option<(DateTime<Local>, &str)> {
+    if line.starts_with(|c: char| c.is_ascii_digit()) {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let time = DateTime::parse_from_rfc3339(timestamp).ok()?;
+        return Some((time.with_timezone(&Local), rest));
+    }
+
+    let timestamp = line.get(..15)?;
+    let parse = |year: i32| {
+        let naive = NaiveDateTime::parse_from_str(&format!("{} {}", year, timestamp), "%Y %b %e %H:%M:%S").ok()?;
+        Local.from_local_datetime(&naive).earliest()
+    };
+    let mut time = parse(modified.year())?;
+    // December entries in a file last written in January.
+    if time > modified + chrono::Duration::days(1) {
+        time = parse(modified.year() - 1)?;
+    }
+    Some((time, &line[15..]))
+}
+
+/// `<user> from <address> port <port>...`
+fn user_and_source(rest: &str) -> Option<(String, String, Option<String>)> {
+    let (user, source) = rest.rsplit_once(" from ")?;
+    let mut fields = source.split(' ');
+    let address = fields.next()?.to_string();
+    let port = match fields.next() {
+        Some("port") => fields.next().map(String::from),
+        _ => None,
+    };
+    Some((user.to_string(), address, port))
+}
+
+/// `[invalid |authenticating ]user <user> <address> port <port>` or `<address> port <port>`.
+fn disconnected(rest: &str) -> Option<(Option<String>, String)> {
+    let (before, _) = rest.split_once(" port ")?;
+    let (user, source) = match before.rsplit_once(' ') {
+        Some((user, source)) => {
+            let user = ["invalid user ", "authenticating user ", "user "]
+                .iter()
+                .find_map(|prefix| user.strip_prefix(prefix))
+                .unwrap_or(user);
+            (Some(user.to_string()), source)
+        }
+        None => (None, before),
+    };
+    Some((user, source.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modified() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+    }
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(year, month, day, hour, minute, second).unwrap().with_timezone(&Utc)
+    }
+
+    fn parse(line: &str) -> Option<SshEvent> {
+        parse_line(line, modified())
+    }
+
+    #[test]
+    fn parses_accepted_login() {
+        let line = "2024-05-01T10:00:00.123456+02:00 web sshd[100]: Accepted publickey for alice from 203.0.113.5 port 50000 ssh2: ED25519 SHA256:abc";
+        let event = parse(line).unwrap();
+        assert_eq!(event.kind, EventKind::Accepted);
+        assert_eq!(event.user.as_deref(), Some("alice"));
+        assert_eq!(event.source.as_deref(), Some("203.0.113.5"));
+        assert_eq!(event.time, Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap() + chrono::Duration::microseconds(123456));
+        assert_eq!(event.count, 1);
+
+        // OpenSSH 9.8 and later.
+        let event = parse("May  1 09:00:01 web sshd-session[102]: Accepted password for bob from 2001:db8::1 port 22 ssh2").unwrap();
+        assert_eq!(event.user.as_deref(), Some("bob"));
+        assert_eq!(event.source.as_deref(), Some("2001:db8::1"));
+        assert_eq!(event.time, local(2024, 5, 1, 9, 0, 1));
+    }
+
+    #[test]
+    fn parses_failed_passwords() {
+        let event = parse("May  1 09:00:00 web sshd[101]: Failed password for root from 198.51.100.7 port 4002 ssh2").unwrap();
+        assert_eq!(event.kind, EventKind::Failed);
+        assert_eq!(event.user.as_deref(), Some("root"));
+        assert_eq!(event.port.as_deref(), Some("4002"));
+
+        let event = parse("May  1 09:00:00 web sshd[101]: Failed password for invalid user admin from 198.51.100.7 port 4001 ssh2").unwrap();
+        assert_eq!(event.kind, EventKind::InvalidUser);
+        assert_eq!(event.user.as_deref(), Some("admin"));
+        assert_eq!(event.source.as_deref(), Some("198.51.100.7"));
+        assert_eq!(event.port.as_deref(), Some("4001"));
+
+        let event = parse("May  1 09:00:00 web sshd[101]: Invalid user admin from 198.51.100.7 port 4001").unwrap();
+        assert_eq!(event.kind, EventKind::InvalidUserConnection);
+        assert_eq!(event.port.as_deref(), Some("4001"));
+    }
+
+    #[test]
+    fn counts_every_password_tried_for_invalid_users() {
+        let mut events: Vec<_> = [
+            // Three passwords on one connection.
+            "May  1 09:00:00 web sshd[101]: Invalid user admin from 198.51.100.7 port 4001",
+            "May  1 09:00:01 web sshd[101]: Failed password for invalid user admin from 198.51.100.7 port 4001 ssh2",
+            "May  1 09:00:02 web sshd[101]: message repeated 2 times: [ Failed password for invalid user admin from 198.51.100.7 port 4001 ssh2]",
+            // A probe that gives up before trying one.
+            "May  1 09:00:03 web sshd[102]: Invalid user test from 198.51.100.7 port 4002",
+            "May  1 09:00:03 web sshd[102]: Disconnected from invalid user test 198.51.100.7 port 4002 [preauth]",
+        ]
+        .iter()
+        .filter_map(|line| parse(line))
+        .collect();
+        count_invalid_user_connections(&mut events);
+
+        let invalid: Vec<_> = events
+            .iter()
+            .filter(|event| event.kind == EventKind::InvalidUser)
+            .map(|event| (event.user.as_deref().unwrap(), event.count))
+            .collect();
+        assert_eq!(invalid, [("admin", 1), ("admin", 2), ("test", 1)]);
+        assert!(events.iter().all(|event| event.kind != EventKind::InvalidUserConnection));
+    }
+
+    #[test]
+    fn expands_repeated_messages() {
+        let line = "May  1 09:00:00 web sshd[101]: message repeated 3 times: [ Failed password for root from 198.51.100.7 port 4002 ssh2]";
+        let event = parse(line).unwrap();
+        assert_eq!(event.kind, EventKind::Failed);
+        assert_eq!(event.count, 3);
+        assert_eq!(event.source.as_deref(), Some("198.51.100.7"));
+
+        assert!(parse("May  1 09:00:00 web sshd[101]: message repeated x times: [ Failed password for root from 198.51.100.7 port 1 ssh2]").is_none());
+        assert!(parse("May  1 09:00:00 web sshd[101]: message repeated 3 times: [ Failed password for root").is_none());
+    }
+
+    #[test]
+    fn parses_disconnects() {
+        let event = parse("May  1 09:00:00 web sshd[101]: Disconnected from authenticating user root 198.51.100.7 port 4000 [preauth]").unwrap();
+        assert_eq!(event.kind, EventKind::Disconnected);
+        assert_eq!(event.user.as_deref(), Some("root"));
+        assert_eq!(event.source.as_deref(), Some("198.51.100.7"));
+
+        let event = parse("May  1 09:00:00 web sshd[101]: Disconnected from 198.51.100.7 port 4000").unwrap();
+        assert_eq!(event.user, None);
+        assert_eq!(event.source.as_deref(), Some("198.51.100.7"));
+
+        let event = parse("May  1 09:00:00 web sshd[101]: Received disconnect from 198.51.100.7 port 4000:11: Bye Bye [preauth]").unwrap();
+        assert_eq!(event.kind, EventKind::Disconnected);
+        assert_eq!(event.source.as_deref(), Some("198.51.100.7"));
+    }
+
+    #[test]
+    fn ignores_other_lines() {
+        for line in [
+            "",
+            "garbage",
+            "May  1 09:00:00 web CRON[5]: pam_unix(cron:session): session opened for user root",
+            "May  1 09:00:00 web sshd[101]: Server listening on 0.0.0.0 port 22.",
+            "May  1 09:00:00 web sshd[101]: Accepted publickey for",
+            "May 41 09:00:00 web sshd[101]: Accepted password for bob from 192.0.2.1 port 22 ssh2",
+            "2024-13-01T10:00:00+02:00 web sshd[101]: Accepted password for bob from 192.0.2.1 port 22 ssh2",
+        ] {
+            assert!(parse(line).is_none(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn puts_december_entries_in_the_previous_year() {
+        let modified = Local.with_ymd_and_hms(2024, 1, 2, 8, 0, 0).unwrap();
+        let event = parse_line("Dec 31 23:00:00 web sshd[1]: Invalid user test from 192.0.2.1 port 1", modified).unwrap();
+        assert_eq!(event.time, local(2023, 12, 31, 23, 0, 0));
+    }
+}
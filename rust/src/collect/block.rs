@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::Path;
+
+use super::{read_trimmed, selected};
+use crate::report::BlockDevice;
+
+/// `/sys/block` and `/sys/class/block` count sizes in 512-byte sectors.
+const SECTOR_SIZE: u64 = 512;
+
+/// The block device tree: every device in `/sys/block` that is not built on
+/// another one, with its partitions and holders below it. Unattached loop
+/// devices, unused zram devices and RAM disks are left out.
+///
+/// `mount_points` gets the `major:minor` and kernel name of each device.
+pub(super) fn read_block_devices(mount_points: &impl Fn(&str, &str) -> Vec<String>) -> Vec<BlockDevice> {
+    let mut roots: Vec<_> = list_dir(Path::new("/sys/block"))
+        .into_iter()
+        .filter(|name| !name.starts_with("ram"))
+        .filter(|name| list_dir(&Path::new("/sys/block").join(name).join("slaves")).is_empty())
+        .filter_map(|name| block_device(&name, None, mount_points))
+        .filter(|device| device.size_bytes > 0 || !device.children.is_empty())
+        .collect();
+    // Loop devices after the disks, as lsblk shows them.
+    roots.sort_by_key(|device| device.kind == "loop");
+    roots
+}
+
+fn block_device(name: &str, parent: Option<&BlockDevice>, mount_points: &impl Fn(&str, &str) -> Vec<String>) -> Option<BlockDevice> {
+    let dir = Path::new("/sys/class/block").join(name);
+    let read = |file: &str| read_trimmed(dir.join(file));
+    let is_partition = dir.join("partition").exists();
+
+    let mut device = BlockDevice {
+        name: name.to_string(),
+        kind: kind(name, is_partition, read("dm/uuid").as_deref(), read("md/level")),
+        size_bytes: read("size")?.parse::<u64>().ok()? * SECTOR_SIZE,
+        // Partitions share the queue of their disk.
+        rotational: match read("queue/rotational") {
+            Some(rotational) => rotational == "1",
+            None => parent.is_some_and(|parent| parent.rotational),
+        },
+        scheduler: read("queue/scheduler")
+            .and_then(|schedulers| selected(&schedulers))
+            .or_else(|| parent.and_then(|parent| parent.scheduler.clone())),
+        read_only: read("ro").as_deref() == Some("1"),
+        removable: read("removable").as_deref() == Some("1"),
+        model: read("device/model"),
+        mapper_name: read("dm/name"),
+        backing_file: read("loop/backing_file"),
+        mount_points: read("dev").map(|device_id| mount_points(&device_id, name)).unwrap_or_default(),
+        children: Vec::new(),
+    };
+
+    let partitions = if is_partition {
+        Vec::new()
+    } else {
+        list_dir(&dir).into_iter().filter(|entry| entry.starts_with(name) && dir.join(entry).join("partition").exists()).collect()
+    };
+    let holders = list_dir(&dir.join("holders"));
+    device.children = partitions
+        .iter()
+        .chain(&holders)
+        .filter_map(|child| block_device(child, Some(&device), mount_points))
+        .collect();
+    Some(device)
+}
+
+/// What lsblk prints as TYPE, from the device's `dm/uuid` and `md/level`.
+fn kind(name: &str, is_partition: bool, dm_uuid: Option<&str>, md_level: Option<String>) -> String {
+    if is_partition {
+        return "part".to_string();
+    }
+    if let Some(uuid) = dm_uuid {
+        let kind = match uuid.split_once('-').map(|(prefix, _)| prefix) {
+            Some("LVM") => "lvm",
+            Some("CRYPT") => "crypt",
+            Some("mpath") => "mpath",
+            _ => "dm",
+        };
+        return kind.to_string();
+    }
+    if let Some(level) = md_level {
+        return level;
+    }
+    if name.starts_with("loop") { "loop" } else { "disk" }.to_string()
+}
+
+/// Entry names of a directory, sorted; empty when it cannot be read.
+fn list_dir(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<_> = entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()).collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_devices_like_lsblk() {
+        assert_eq!(kind("sda", false, None, None), "disk");
+        assert_eq!(kind("nvme0n1p1", true, None, None), "part");
+        assert_eq!(kind("loop0", false, None, None), "loop");
+        assert_eq!(kind("md0", false, None, Some("raid1".to_string())), "raid1");
+        assert_eq!(kind("dm-0", false, Some("LVM-3f2a9cVq1xPZb0pR8kBbnm"), None), "lvm");
+        assert_eq!(kind("dm-1", false, Some("CRYPT-LUKS2-1b2c3d4e-luks-1b2c"), None), "crypt");
+        assert_eq!(kind("dm-2", false, Some("mpath-3600508b400105e210000900000490000"), None), "mpath");
+        assert_eq!(kind("dm-3", false, Some("custom"), None), "dm");
+        // Partitions of a device mapper volume are still partitions.
+        assert_eq!(kind("dm-4", true, Some("part1-LVM-3f2a"), None), "part");
+    }
+}
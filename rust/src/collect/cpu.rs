@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::Path;
+
+use sysinfo::{CpuRefreshKind, System};
+
+use super::{Collector, read_trimmed};
+use crate::report::{CoreUsage, CpuTimes, CpuUsage};
+
+/// Average and per-core CPU usage and frequency, with the time spent in each
+/// mode from `/proc/stat`.
+///
+/// Usage is measured between two refreshes, which have to be at least
+/// [`sysinfo::MINIMUM_CPU_UPDATE_INTERVAL`] apart.
+pub struct CpuCollector {
+    sys: System,
+    /// `/proc/stat` lines of the previous and the last refresh.
+    previous_stat: Vec<StatLine>,
+    stat: Vec<StatLine>,
+}
+
+/// One `cpu` or `cpuN` line of `/proc/stat`.
+#[derive(Debug, Clone)]
+struct StatLine {
+    /// `None` for the aggregated `cpu` line.
+    core: Option<usize>,
+    /// Clock ticks spent in user, nice, system, idle, iowait, irq, softirq
+    /// and steal. Guest time is already part of user and nice.
+    ticks: [u64; 8],
+}
+
+impl CpuCollector {
+    pub fn new() -> Self {
+        CpuCollector {
+            sys: System::new(),
+            previous_stat: Vec::new(),
+            stat: Vec::new(),
+        }
+    }
+
+    /// Usage of each logical core as of the last refresh.
+    pub fn per_core_usage(&self) -> Vec<f32> {
+        self.sys.cpus().iter().map(|cpu| cpu.cpu_usage()).collect()
+    }
+
+    /// Share of each mode between the last two refreshes for one line.
+    fn times(&self, core: Option<usize>) -> Option<CpuTimes> {
+        let before = self.previous_stat.iter().find(|line| line.core == core)?;
+        let after = self.stat.iter().find(|line| line.core == core)?;
+
+        let delta: Vec<u64> = after.ticks.iter().zip(before.ticks).map(|(after, before)| after.saturating_sub(before)).collect();
+        let total: u64 = delta.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let share = |mode: usize| delta[mode] as f64 / total as f64 * 100.0;
+
+        Some(CpuTimes {
+            user: share(0),
+            nice: share(1),
+            system: share(2),
+            idle: share(3),
+            iowait: share(4),
+            irq: share(5),
+            softirq: share(6),
+            steal: share(7),
+        })
+    }
+}
+
+impl Default for CpuCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for CpuCollector {
+    type Output = CpuUsage;
+
+    fn refresh(&mut self) {
+        self.sys
+            .refresh_cpu_specifics(CpuRefreshKind::nothing().with_cpu_usage().with_frequency());
+        self.previous_stat = std::mem::replace(&mut self.stat, read_proc_stat());
+    }
+
+    fn collect(&self) -> CpuUsage {
+        let cpus = self.sys.cpus();
+        let cpu_usage: f32 = cpus.iter().map(|cpu| cpu.cpu_usage()).sum::<f32>() / cpus.len() as f32;
+
+        let per_core = cpus
+            .iter()
+            .enumerate()
+            .map(|(index, cpu)| {
+                let core = cpu.name().strip_prefix("cpu").and_then(|number| number.parse().ok()).unwrap_or(index);
+                let cpufreq = format!("/sys/devices/system/cpu/cpu{}/cpufreq", core);
+                CoreUsage {
+                    core,
+                    usage_percent: cpu.cpu_usage(),
+                    // sysinfo falls back to `cpu MHz` of /proc/cpuinfo, 0 when neither is there.
+                    frequency_mhz: read_khz(&cpufreq, "scaling_cur_freq")
+                        .or_else(|| (cpu.frequency() > 0).then(|| cpu.frequency())),
+                    min_frequency_mhz: read_khz(&cpufreq, "cpuinfo_min_freq"),
+                    max_frequency_mhz: read_khz(&cpufreq, "cpuinfo_max_freq"),
+                    governor: read_trimmed(Path::new(&cpufreq).join("scaling_governor")),
+                    times: self.times(Some(core)),
+                }
+            })
+            .collect();
+
+        let first = cpus.first();
+        CpuUsage {
+            usage_percent: cpu_usage,
+            idle_percent: 100.0 - cpu_usage,
+            cores: cpus.len(),
+            model: first.map(|cpu| cpu.brand().trim()).filter(|brand| !brand.is_empty()).map(String::from),
+            vendor: first.map(|cpu| cpu.vendor_id()).filter(|vendor| !vendor.is_empty()).map(String::from),
+            times: self.times(None),
+            per_core,
+        }
+    }
+}
+
+/// The `cpu` lines of `/proc/stat`; empty when it cannot be read.
+fn read_proc_stat() -> Vec<StatLine> {
+    let Ok(contents) = fs::read_to_string("/proc/stat") else {
+        return Vec::new();
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let core = match fields.next()?.strip_prefix("cpu")? {
+                "" => None,
+                number => Some(number.parse().ok()?),
+            };
+            let mut ticks = [0; 8];
+            for (tick, field) in ticks.iter_mut().zip(fields) {
+                *tick = field.parse().ok()?;
+            }
+            Some(StatLine { core, ticks })
+        })
+        .collect()
+}
+
+/// cpufreq reports frequencies in kHz.
+fn read_khz(dir: &str, file: &str) -> Option<u64> {
+    read_trimmed(Path::new(dir).join(file))?.parse::<u64>().ok().map(|khz| khz / 1000)
+}
@@ -0,0 +1,501 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::CString;
+use std::fs;
+use std::mem::MaybeUninit;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::block::read_block_devices;
+use super::diskstats::DiskStats;
+use super::{Collector, percent, unescape};
+use crate::report::{BlockDevice, DiskForecast, DiskIo, DiskUsage, InodeUsage};
+
+/// Filesystems without storage of their own, or whose usage says nothing
+/// about disks. Left out unless named in an include list.
+const PSEUDO_FS_TYPES: [&str; 27] = [
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "fuse.lxcfs",
+    "fuse.portal",
+    "hugetlbfs",
+    "iso9660",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rootfs",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "squashfs",
+    "sysfs",
+    "tracefs",
+];
+
+/// tmpfs is memory, not disk; statvfs can hang on hard-mounted network
+/// filesystems. Both can be listed with an include list.
+const DEFAULT_IGNORED_FS_TYPES: [&str; 6] = ["tmpfs", "nfs", "nfs4", "cifs", "smb3", "9p"];
+
+/// Shortest history a forecast is made from, unless the forecast window is
+/// very short; seconds of writes say nothing about days.
+const MIN_FORECAST_SPAN: Duration = Duration::from_secs(5 * 60);
+
+/// One line of `/proc/self/mountinfo`.
+#[derive(Debug, Clone)]
+struct Mount {
+    /// `major:minor` of the device, shared by every bind mount of it.
+    device_id: String,
+    /// Directory of the filesystem mounted here, `/` unless it is a bind mount.
+    root: String,
+    mount_point: String,
+    options: Vec<String>,
+    fs_type: String,
+    source: String,
+}
+
+/// Space and inode usage of every mounted filesystem, read from
+/// `/proc/self/mountinfo` and `statvfs`. Pseudo filesystems are skipped and
+/// each device is listed once, however often it is bind mounted.
+///
+/// Block device activity comes from `/proc/diskstats` and is measured
+/// between two refreshes, see [`DiskCollector::io`]. Refreshes also record
+/// the used space of every filesystem to forecast when it fills up.
+pub struct DiskCollector {
+    disks: Vec<DiskUsage>,
+    /// Mount points behind `disks` by `major:minor` and by the kernel name
+    /// of their source device, rebuilt on every refresh.
+    device_mount_points: HashMap<String, Vec<String>>,
+    previous_stats: Option<DiskStats>,
+    stats: Option<DiskStats>,
+    /// Used bytes per mount point, oldest first, within `forecast_window`.
+    history: HashMap<String, VecDeque<(Instant, u64)>>,
+    forecast_window: Duration,
+    details: bool,
+    include_mount_points: Vec<String>,
+    ignore_mount_points: Vec<String>,
+    include_fs_types: Vec<String>,
+    ignore_fs_types: Vec<String>,
+}
+
+impl DiskCollector {
+    pub fn new() -> Self {
+        DiskCollector {
+            disks: Vec::new(),
+            device_mount_points: HashMap::new(),
+            previous_stats: None,
+            stats: None,
+            history: HashMap::new(),
+            forecast_window: Duration::from_secs(60 * 60),
+            details: true,
+            include_mount_points: Vec::new(),
+            ignore_mount_points: Vec::new(),
+            include_fs_types: Vec::new(),
+            ignore_fs_types: Vec::new(),
+        }
+    }
+
+    /// How far back used space is remembered for the fill-rate forecast.
+    pub fn with_forecast_window(mut self, window: Duration) -> Self {
+        self.forecast_window = window;
+        self
+    }
+
+    /// Whether refreshes also read `/proc/diskstats` and keep the used space
+    /// history for forecasts. On by default; without them
+    /// [`DiskCollector::io`] is empty and no forecast is made.
+    pub fn with_details(mut self, details: bool) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// A quarter of the forecast window and at least [`MIN_FORECAST_SPAN`],
+    /// but no more than half of the window, which the history never exceeds.
+    fn min_forecast_span(&self) -> Duration {
+        (self.forecast_window / 4).max(MIN_FORECAST_SPAN).min(self.forecast_window / 2)
+    }
+
+    /// Only lists filesystems mounted at one of these paths.
+    pub fn include_mount_points(mut self, mount_points: Vec<String>) -> Self {
+        self.include_mount_points = mount_points;
+        self
+    }
+
+    /// Leaves out filesystems mounted at one of these paths.
+    pub fn ignore_mount_points(mut self, mount_points: Vec<String>) -> Self {
+        self.ignore_mount_points = mount_points;
+        self
+    }
+
+    /// Only lists filesystems of these types, pseudo filesystems included,
+    /// e.g. `["ext4", "xfs", "tmpfs"]`.
+    pub fn include_fs_types(mut self, fs_types: Vec<String>) -> Self {
+        self.include_fs_types = fs_types;
+        self
+    }
+
+    /// Leaves out filesystems of these types, e.g. `vfat`.
+    pub fn ignore_fs_types(mut self, fs_types: Vec<String>) -> Self {
+        self.ignore_fs_types = fs_types;
+        self
+    }
+
+    /// I/O of the devices behind the listed filesystems and of every other
+    /// physical disk that has seen I/O since boot. Rates cover the time
+    /// between the last two refreshes.
+    pub fn io(&self) -> Vec<DiskIo> {
+        let Some(stats) = &self.stats else {
+            return Vec::new();
+        };
+        let seconds = self
+            .previous_stats
+            .as_ref()
+            .map(|previous| stats.taken.duration_since(previous.taken).as_secs_f64());
+
+        stats
+            .devices
+            .iter()
+            .filter_map(|device| {
+                let mount_points = self.mount_points(&device.device_id, &device.name);
+                if mount_points.is_empty() && (!device.is_whole_disk() || device.is_virtual() || device.is_idle_since_boot()) {
+                    return None;
+                }
+                let previous = self.previous_stats.as_ref().and_then(|previous| previous.find(&device.device_id));
+                Some(device.io(mount_points, previous.zip(seconds)))
+            })
+            .collect()
+    }
+
+    /// Disks, partitions, device mapper volumes, md arrays and loop devices
+    /// from `/sys/block` as a tree, with the mount points of the last refresh.
+    pub fn block_devices(&self) -> Vec<BlockDevice> {
+        read_block_devices(&|device_id, name| self.mount_points(device_id, name))
+    }
+
+    /// Listed mount points on the device with this `major:minor` and kernel
+    /// name. btrfs reports an anonymous device number and device mapper
+    /// sources are symlinks to dm-N, so both are matched by name too.
+    fn mount_points(&self, device_id: &str, name: &str) -> Vec<String> {
+        let mut mount_points: Vec<String> = Vec::new();
+        for key in [device_id, name] {
+            for mount_point in self.device_mount_points.get(key).into_iter().flatten() {
+                if !mount_points.contains(mount_point) {
+                    mount_points.push(mount_point.clone());
+                }
+            }
+        }
+        mount_points
+    }
+
+    fn wants_fs_type(&self, mount: &Mount) -> bool {
+        let fs_type = mount.fs_type.as_str();
+        let type_wanted = if self.include_fs_types.is_empty() {
+            // Container layers, except the container's own root.
+            let layer = fs_type == "overlay" && mount.mount_point != "/";
+            !layer && !PSEUDO_FS_TYPES.contains(&fs_type) && !DEFAULT_IGNORED_FS_TYPES.contains(&fs_type)
+        } else {
+            self.include_fs_types.iter().any(|included| included == fs_type)
+        };
+        type_wanted && !self.ignore_fs_types.iter().any(|ignored| ignored == fs_type)
+    }
+
+    fn wants_mount_point(&self, mount: &Mount) -> bool {
+        let mount_point = mount.mount_point.as_str();
+        (self.include_mount_points.is_empty() || self.include_mount_points.iter().any(|included| included == mount_point))
+            && !self.ignore_mount_points.iter().any(|ignored| ignored == mount_point)
+    }
+}
+
+impl Default for DiskCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for DiskCollector {
+    type Output = Vec<DiskUsage>;
+
+    fn refresh(&mut self) {
+        let mounts: Vec<_> = read_mountinfo().into_iter().filter(|mount| self.wants_fs_type(mount)).collect();
+        // Mount point filters apply to the mount a device is listed under, so
+        // ignoring `/` does not bring back a bind mount of the root device.
+        let mounts: Vec<_> = dedupe(mounts).into_iter().filter(|mount| self.wants_mount_point(mount)).collect();
+        self.disks = mounts.iter().filter_map(disk_usage).collect();
+
+        // Device ids contain a colon, kernel names never do, so they can
+        // share one map.
+        self.device_mount_points.clear();
+        for mount in &mounts {
+            let keys = [Some(mount.device_id.clone()), source_device(mount)];
+            for key in keys.into_iter().flatten() {
+                self.device_mount_points.entry(key).or_default().push(mount.mount_point.clone());
+            }
+        }
+        if !self.details {
+            return;
+        }
+        self.previous_stats = std::mem::replace(&mut self.stats, DiskStats::read());
+
+        let now = Instant::now();
+        let min_span = self.min_forecast_span();
+        let disks = &mut self.disks;
+        self.history.retain(|mount_point, _| disks.iter().any(|disk| disk.mount_point == *mount_point));
+        for disk in disks {
+            let samples = self.history.entry(disk.mount_point.clone()).or_default();
+            samples.push_back((now, disk.used_bytes));
+            while samples.front().is_some_and(|(taken, _)| now.duration_since(*taken) > self.forecast_window) {
+                samples.pop_front();
+            }
+            disk.forecast = forecast(samples, disk.available_bytes, min_span);
+        }
+    }
+
+    fn collect(&self) -> Vec<DiskUsage> {
+        self.disks.clone()
+    }
+}
+
+/// Drops mounts hidden by a later mount on the same path, then keeps one
+/// mount per device: the one of the whole filesystem (root `/`) with the
+/// shortest mount point, in mount order.
+fn dedupe(mut mounts: Vec<Mount>) -> Vec<Mount> {
+    let mut seen = HashSet::new();
+    mounts.reverse();
+    mounts.retain(|mount| seen.insert(mount.mount_point.clone()));
+    mounts.reverse();
+
+    let mut best: HashMap<&str, &Mount> = HashMap::new();
+    for mount in &mounts {
+        let rank = |mount: &Mount| (mount.root != "/", mount.mount_point.len());
+        best.entry(&mount.device_id)
+            .and_modify(|current| {
+                if rank(mount) < rank(current) {
+                    *current = mount;
+                }
+            })
+            .or_insert(mount);
+    }
+    mounts
+        .iter()
+        .filter(|mount| std::ptr::eq(best[mount.device_id.as_str()], *mount))
+        .cloned()
+        .collect()
+}
+
+/// Least-squares fit of used bytes over time. Needs three samples spanning
+/// at least `min_span`, so a burst of writes right after start-up does not
+/// produce a forecast.
+fn forecast(samples: &VecDeque<(Instant, u64)>, available_bytes: u64, min_span: Duration) -> Option<DiskForecast> {
+    let (first, _) = *samples.front()?;
+    let (last, _) = *samples.back()?;
+    if samples.len() < 3 || last.duration_since(first) < min_span {
+        return None;
+    }
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|(taken, used)| (taken.duration_since(first).as_secs_f64(), *used as f64))
+        .collect();
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance <= 0.0 {
+        return None;
+    }
+
+    let growth = covariance / variance;
+    Some(DiskForecast {
+        growth_bytes_per_second: growth,
+        seconds_until_full: (growth > 0.0).then(|| (available_bytes as f64 / growth) as u64),
+        samples: samples.len(),
+        window_seconds: points.last().map_or(0, |(x, _)| *x as u64),
+    })
+}
+
+/// Kernel name of the block device a mount's source points to, e.g. `dm-0`
+/// for `/dev/mapper/vg-root`.
+fn source_device(mount: &Mount) -> Option<String> {
+    let source = fs::canonicalize(&mount.source).ok()?;
+    let name = source.strip_prefix("/dev").ok()?.file_name()?;
+    Path::new("/sys/class/block").join(name).exists().then(|| name.to_string_lossy().into_owned())
+}
+
+// The statvfs fields are 32 bit on 32-bit targets.
+#[allow(clippy::unnecessary_cast)]
+fn disk_usage(mount: &Mount) -> Option<DiskUsage> {
+    let stat = statvfs(&mount.mount_point)?;
+    let fragment = stat.f_frsize as u64;
+    let total_space = stat.f_blocks as u64 * fragment;
+    let available_space = stat.f_bavail as u64 * fragment;
+    let used_space = total_space.saturating_sub(available_space);
+
+    // Filesystems such as btrfs and vfat have no fixed inode table.
+    let total_inodes = stat.f_files as u64;
+    let used_inodes = total_inodes.saturating_sub(stat.f_ffree as u64);
+    let inodes = (total_inodes > 0).then(|| InodeUsage {
+        total: total_inodes,
+        used: used_inodes,
+        used_percent: percent(used_inodes, total_inodes),
+    });
+
+    Some(DiskUsage {
+        filesystem: mount.source.clone(),
+        mount_point: mount.mount_point.clone(),
+        fs_type: mount.fs_type.clone(),
+        read_only: mount.options.iter().any(|option| option == "ro"),
+        mount_options: mount.options.clone(),
+        total_bytes: total_space,
+        used_bytes: used_space,
+        available_bytes: available_space,
+        used_percent: percent(used_space, total_space),
+        inodes,
+        forecast: None,
+    })
+}
+
+fn statvfs(path: &str) -> Option<libc::statvfs> {
+    let path = CString::new(path).ok()?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is NUL terminated and `stat` is only read after
+    // statvfs reported that it filled it in.
+    unsafe { (libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) == 0).then(|| stat.assume_init()) }
+}
+
+/// `36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue`,
+/// see proc(5).
+fn read_mountinfo() -> Vec<Mount> {
+    fs::read_to_string("/proc/self/mountinfo").map(|mountinfo| parse_mountinfo(&mountinfo)).unwrap_or_default()
+}
+
+fn parse_mountinfo(mountinfo: &str) -> Vec<Mount> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (mount, filesystem) = line.split_once(" - ")?;
+            let mut mount = mount.split(' ');
+            let mut filesystem = filesystem.split(' ');
+            let device_id = mount.nth(2)?;
+            let root = mount.next()?;
+            let mount_point = mount.next()?;
+            let options = mount.next()?;
+
+            Some(Mount {
+                device_id: device_id.to_string(),
+                root: unescape(root),
+                mount_point: unescape(mount_point),
+                options: options.split(',').map(String::from).collect(),
+                fs_type: filesystem.next()?.to_string(),
+                source: unescape(filesystem.next()?),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTINFO: &str = "\
+22 1 254:0 / / rw,relatime shared:1 - ext4 /dev/vda rw
+23 22 0:21 / /proc rw,nosuid,nodev,noexec - proc proc rw
+24 22 254:0 /srv /mnt/my\\040data rw,noatime shared:1 - ext4 /dev/vda rw
+25 22 8:1 / /mnt/usb rw - vfat /dev/sdb1 rw
+26 25 8:2 / /mnt/usb ro - ext4 /dev/sdc1 ro
+not a mountinfo line
+27 22 8:3 / /boot rw shared:2
+";
+
+    #[test]
+    fn parses_mountinfo() {
+        let mounts = parse_mountinfo(MOUNTINFO);
+        let points: Vec<_> = mounts.iter().map(|mount| mount.mount_point.as_str()).collect();
+        assert_eq!(points, ["/", "/proc", "/mnt/my data", "/mnt/usb", "/mnt/usb"]);
+
+        let bind = &mounts[2];
+        assert_eq!(bind.device_id, "254:0");
+        assert_eq!(bind.root, "/srv");
+        assert_eq!(bind.options, ["rw", "noatime"]);
+        assert_eq!(bind.fs_type, "ext4");
+        assert_eq!(bind.source, "/dev/vda");
+        assert_eq!(mounts[1].options, ["rw", "nosuid", "nodev", "noexec"]);
+    }
+
+    #[test]
+    fn dedupe_keeps_visible_whole_filesystems() {
+        let mounts = dedupe(parse_mountinfo(MOUNTINFO));
+        let points: Vec<_> = mounts.iter().map(|mount| (mount.device_id.as_str(), mount.mount_point.as_str())).collect();
+        // The bind mount of /srv is dropped, the vfat mount is hidden by the ext4 one.
+        assert_eq!(points, [("254:0", "/"), ("0:21", "/proc"), ("8:2", "/mnt/usb")]);
+    }
+
+    #[test]
+    fn filters_pseudo_filesystems_and_container_layers() {
+        let mount = |fs_type: &str, mount_point: &str| {
+            parse_mountinfo(&format!("1 0 0:1 / {} rw - {} none rw", mount_point, fs_type)).remove(0)
+        };
+        let collector = DiskCollector::new();
+        assert!(collector.wants_fs_type(&mount("ext4", "/")));
+        assert!(collector.wants_fs_type(&mount("overlay", "/")));
+        assert!(!collector.wants_fs_type(&mount("overlay", "/var/lib/docker/overlay2/abc/merged")));
+        assert!(!collector.wants_fs_type(&mount("proc", "/proc")));
+
+        let collector = DiskCollector::new().include_fs_types(vec!["proc".to_string()]);
+        assert!(collector.wants_fs_type(&mount("proc", "/proc")));
+        assert!(!collector.wants_fs_type(&mount("ext4", "/")));
+
+        let collector = DiskCollector::new().ignore_fs_types(vec!["ext4".to_string()]).ignore_mount_points(vec!["/boot".to_string()]);
+        assert!(!collector.wants_fs_type(&mount("ext4", "/")));
+        assert!(!collector.wants_mount_point(&mount("xfs", "/boot")));
+        assert!(collector.wants_mount_point(&mount("xfs", "/srv")));
+    }
+
+    fn samples(used: &[(u64, u64)]) -> VecDeque<(Instant, u64)> {
+        let start = Instant::now();
+        used.iter().map(|&(seconds, used)| (start + Duration::from_secs(seconds), used)).collect()
+    }
+
+    #[test]
+    fn forecasts_linear_growth() {
+        let forecast = forecast(&samples(&[(0, 1000), (60, 1600), (120, 2200)]), 1000, Duration::from_secs(60)).unwrap();
+        assert!((forecast.growth_bytes_per_second - 10.0).abs() < 1e-9);
+        assert_eq!(forecast.seconds_until_full, Some(100));
+        assert_eq!(forecast.samples, 3);
+        assert_eq!(forecast.window_seconds, 120);
+    }
+
+    #[test]
+    fn no_time_until_full_while_shrinking() {
+        let forecast = forecast(&samples(&[(0, 3000), (60, 2000), (120, 1000)]), 1000, Duration::ZERO).unwrap();
+        assert!(forecast.growth_bytes_per_second < 0.0);
+        assert_eq!(forecast.seconds_until_full, None);
+    }
+
+    #[test]
+    fn no_forecast_from_too_few_or_too_close_samples() {
+        assert!(forecast(&samples(&[]), 1000, Duration::ZERO).is_none());
+        assert!(forecast(&samples(&[(0, 1000), (60, 2000)]), 1000, Duration::ZERO).is_none());
+        assert!(forecast(&samples(&[(0, 1000), (1, 2000), (2, 3000)]), 1000, MIN_FORECAST_SPAN).is_none());
+        // Samples taken at the same instant have no slope.
+        assert!(forecast(&samples(&[(0, 1000), (0, 2000), (0, 3000)]), 1000, Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn minimum_forecast_span_fits_the_window() {
+        let span = |window: u64| DiskCollector::new().with_forecast_window(Duration::from_secs(window)).min_forecast_span();
+        assert_eq!(span(4 * 3600), Duration::from_secs(3600));
+        assert_eq!(span(3600), Duration::from_secs(900));
+        assert_eq!(span(15 * 60), MIN_FORECAST_SPAN);
+        // Never longer than the history can cover.
+        assert_eq!(span(8), Duration::from_secs(4));
+    }
+}
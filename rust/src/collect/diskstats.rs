@@ -0,0 +1,173 @@
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::report::{DiskIo, DiskIoRates};
+
+/// `/proc/diskstats` counts in 512-byte sectors whatever the device uses.
+const SECTOR_SIZE: u64 = 512;
+
+/// One line of `/proc/diskstats`.
+#[derive(Debug, Clone)]
+pub(super) struct DiskStat {
+    /// `major:minor`, as in `/proc/self/mountinfo`.
+    pub device_id: String,
+    pub name: String,
+    reads: u64,
+    read_sectors: u64,
+    read_ms: u64,
+    writes: u64,
+    written_sectors: u64,
+    write_ms: u64,
+    in_flight: u64,
+    io_ms: u64,
+    weighted_io_ms: u64,
+}
+
+/// A `/proc/diskstats` snapshot with the time it was taken.
+#[derive(Debug, Clone)]
+pub(super) struct DiskStats {
+    pub taken: Instant,
+    pub devices: Vec<DiskStat>,
+}
+
+impl DiskStats {
+    pub fn read() -> Option<DiskStats> {
+        let diskstats = fs::read_to_string("/proc/diskstats").ok()?;
+        let devices = diskstats.lines().filter_map(parse_line).collect();
+        Some(DiskStats { taken: Instant::now(), devices })
+    }
+
+    pub fn find(&self, device_id: &str) -> Option<&DiskStat> {
+        self.devices.iter().find(|device| device.device_id == device_id)
+    }
+}
+
+impl DiskStat {
+    /// Whole disks have a `/sys/block` entry, partitions do not.
+    pub fn is_whole_disk(&self) -> bool {
+        Path::new("/sys/block").join(&self.name).exists()
+    }
+
+    /// Loop and RAM disks, which only mirror I/O of other devices or memory.
+    pub fn is_virtual(&self) -> bool {
+        ["loop", "ram", "zram"].iter().any(|prefix| self.name.starts_with(prefix))
+    }
+
+    pub fn is_idle_since_boot(&self) -> bool {
+        self.reads == 0 && self.writes == 0
+    }
+
+    /// Totals, with rates against `previous` when it is an earlier sample
+    /// of the same device.
+    pub fn io(&self, mount_points: Vec<String>, previous: Option<(&DiskStat, f64)>) -> DiskIo {
+        DiskIo {
+            device: self.name.clone(),
+            mount_points,
+            reads_completed: self.reads,
+            writes_completed: self.writes,
+            read_bytes: self.read_sectors * SECTOR_SIZE,
+            written_bytes: self.written_sectors * SECTOR_SIZE,
+            read_time_ms: self.read_ms,
+            write_time_ms: self.write_ms,
+            io_time_ms: self.io_ms,
+            weighted_io_time_ms: self.weighted_io_ms,
+            in_flight: self.in_flight,
+            rates: previous.and_then(|(previous, seconds)| self.rates(previous, seconds)),
+        }
+    }
+
+    fn rates(&self, previous: &DiskStat, seconds: f64) -> Option<DiskIoRates> {
+        if seconds <= 0.0 {
+            return None;
+        }
+        // Counters wrap on 32-bit kernels and reset when a device is re-added.
+        let delta = |after: u64, before: u64| after.saturating_sub(before) as f64;
+        let reads = delta(self.reads, previous.reads);
+        let writes = delta(self.writes, previous.writes);
+        let average = |ms: f64, requests: f64| if requests > 0.0 { ms / requests } else { 0.0 };
+
+        Some(DiskIoRates {
+            reads_per_second: reads / seconds,
+            writes_per_second: writes / seconds,
+            read_bytes_per_second: delta(self.read_sectors, previous.read_sectors) * SECTOR_SIZE as f64 / seconds,
+            write_bytes_per_second: delta(self.written_sectors, previous.written_sectors) * SECTOR_SIZE as f64 / seconds,
+            read_await_ms: average(delta(self.read_ms, previous.read_ms), reads),
+            write_await_ms: average(delta(self.write_ms, previous.write_ms), writes),
+            queue_depth: delta(self.weighted_io_ms, previous.weighted_io_ms) / 1000.0 / seconds,
+            utilization_percent: (delta(self.io_ms, previous.io_ms) / 1000.0 / seconds * 100.0).min(100.0),
+        })
+    }
+}
+
+/// `major minor name reads merged sectors ms writes merged sectors ms in_flight io_ms weighted_ms ...`;
+/// newer kernels append discard and flush counters, which are ignored.
+fn parse_line(line: &str) -> Option<DiskStat> {
+    let fields: Vec<_> = line.split_whitespace().collect();
+    let number = |index: usize| fields.get(index)?.parse::<u64>().ok();
+
+    Some(DiskStat {
+        device_id: format!("{}:{}", fields.first()?, fields.get(1)?),
+        name: fields.get(2)?.to_string(),
+        reads: number(3)?,
+        read_sectors: number(5)?,
+        read_ms: number(6)?,
+        writes: number(7)?,
+        written_sectors: number(9)?,
+        write_ms: number(10)?,
+        in_flight: number(11)?,
+        io_ms: number(12)?,
+        weighted_io_ms: number(13)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISKSTATS: &str = "\
+   8       0 sda 1000 10 20000 500 2000 20 40000 800 1 900 1300 0 0 0 0 0 0
+   8       1 sda1 12 0 96 4 0 0 0 0 0 8 4
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0
+   8      16 sdb 12 x 96
+ 253       0
+";
+
+    #[test]
+    fn parses_diskstats() {
+        let devices: Vec<_> = DISKSTATS.lines().filter_map(parse_line).collect();
+        let names: Vec<_> = devices.iter().map(|device| device.name.as_str()).collect();
+        assert_eq!(names, ["sda", "sda1", "loop0"]);
+
+        let sda = &devices[0];
+        assert_eq!(sda.device_id, "8:0");
+        assert_eq!((sda.reads, sda.read_sectors, sda.read_ms), (1000, 20000, 500));
+        assert_eq!((sda.writes, sda.written_sectors, sda.write_ms), (2000, 40000, 800));
+        assert_eq!((sda.in_flight, sda.io_ms, sda.weighted_io_ms), (1, 900, 1300));
+
+        assert!(devices[2].is_virtual());
+        assert!(devices[2].is_idle_since_boot());
+        assert!(!sda.is_idle_since_boot());
+    }
+
+    #[test]
+    fn computes_rates_between_samples() {
+        let before = parse_line("8 0 sda 100 0 1000 100 50 0 2000 50 0 100 200").unwrap();
+        let after = parse_line("8 0 sda 300 0 3000 500 150 0 6000 250 2 600 1200").unwrap();
+
+        let io = after.io(vec!["/".to_string()], Some((&before, 2.0)));
+        assert_eq!(io.read_bytes, 3000 * SECTOR_SIZE);
+        let rates = io.rates.unwrap();
+        assert_eq!(rates.reads_per_second, 100.0);
+        assert_eq!(rates.writes_per_second, 50.0);
+        assert_eq!(rates.read_bytes_per_second, 2000.0 * 512.0 / 2.0);
+        assert_eq!(rates.read_await_ms, 2.0);
+        assert_eq!(rates.write_await_ms, 2.0);
+        assert_eq!(rates.queue_depth, 0.5);
+        assert_eq!(rates.utilization_percent, 25.0);
+
+        // A reset counter does not produce negative rates.
+        assert_eq!(before.rates(&after, 2.0).unwrap().reads_per_second, 0.0);
+        assert!(after.rates(&before, 0.0).is_none());
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::time::Instant;
+
+use sysinfo::{MemoryRefreshKind, System};
+
+use super::{Collector, percent, swap};
+use crate::report::{MemoryDetails, MemoryUsage, SwapDevice, SwapUsage, ZswapStats};
+
+/// Physical memory and swap usage, broken down by `/proc/meminfo`, with swap
+/// activity, swap devices and zram/zswap compression.
+pub struct MemoryCollector {
+    sys: System,
+    details: Option<MemoryDetails>,
+    /// Pages swapped in and out as of the previous and the last refresh.
+    previous_vmstat: Option<(Instant, u64, u64)>,
+    vmstat: Option<(Instant, u64, u64)>,
+    swap_devices: Vec<SwapDevice>,
+    zswap: Option<ZswapStats>,
+}
+
+impl MemoryCollector {
+    pub fn new() -> Self {
+        MemoryCollector {
+            sys: System::new(),
+            details: None,
+            previous_vmstat: None,
+            vmstat: None,
+            swap_devices: Vec::new(),
+            zswap: None,
+        }
+    }
+
+    /// Pages per second swapped in and out between the last two refreshes.
+    fn swap_rates(&self) -> Option<(f64, f64)> {
+        let (before, before_in, before_out) = self.previous_vmstat?;
+        let (after, after_in, after_out) = self.vmstat?;
+        let seconds = after.duration_since(before).as_secs_f64();
+        if seconds <= 0.0 {
+            return None;
+        }
+        let rate = |before: u64, after: u64| after.saturating_sub(before) as f64 / seconds;
+        Some((rate(before_in, after_in), rate(before_out, after_out)))
+    }
+}
+
+impl Default for MemoryCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for MemoryCollector {
+    type Output = MemoryUsage;
+
+    fn refresh(&mut self) {
+        self.sys.refresh_memory_specifics(MemoryRefreshKind::everything());
+        let meminfo = fs::read_to_string("/proc/meminfo").ok();
+        self.details = meminfo.as_deref().map(parse_meminfo);
+
+        self.previous_vmstat = self.vmstat.take();
+        self.vmstat = swap::read_vmstat().map(|(swapped_in, swapped_out)| (Instant::now(), swapped_in, swapped_out));
+        self.swap_devices = swap::read_swaps();
+        let meminfo_value = |name: &str| {
+            meminfo_entries(meminfo.as_deref()?).find(|(entry, _)| *entry == name).map(|(_, bytes)| bytes)
+        };
+        self.zswap = swap::zswap_stats(meminfo_value("Zswap"), meminfo_value("Zswapped"));
+    }
+
+    fn collect(&self) -> MemoryUsage {
+        let sys = &self.sys;
+        let total_memory = sys.total_memory();
+        let used_memory = sys.used_memory();
+        let available_memory = sys.available_memory();
+
+        // Swap information
+        let total_swap = sys.total_swap();
+        let used_swap = sys.used_swap();
+        let rates = self.swap_rates();
+        let swap = (total_swap > 0).then(|| SwapUsage {
+            total_bytes: total_swap,
+            used_bytes: used_swap,
+            used_percent: percent(used_swap, total_swap),
+            swapped_in_pages: self.vmstat.map(|(_, swapped_in, _)| swapped_in),
+            swapped_out_pages: self.vmstat.map(|(_, _, swapped_out)| swapped_out),
+            swap_in_pages_per_second: rates.map(|(swap_in, _)| swap_in),
+            swap_out_pages_per_second: rates.map(|(_, swap_out)| swap_out),
+            devices: self.swap_devices.clone(),
+            zswap: self.zswap.clone(),
+        });
+
+        MemoryUsage {
+            total_bytes: total_memory,
+            used_bytes: used_memory,
+            available_bytes: available_memory,
+            used_percent: percent(used_memory, total_memory),
+            available_percent: percent(available_memory, total_memory),
+            swap,
+            details: self.details.clone(),
+        }
+    }
+}
+
+/// `Name:   1234 kB` lines in bytes; the `HugePages_*` counts have no unit.
+fn meminfo_entries(meminfo: &str) -> impl Iterator<Item = (&str, u64)> {
+    meminfo.lines().filter_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        let mut value = value.split_whitespace();
+        let number = value.next()?.parse::<u64>().ok()?;
+        Some((name, if value.next() == Some("kB") { number * 1024 } else { number }))
+    })
+}
+
+fn parse_meminfo(meminfo: &str) -> MemoryDetails {
+    let mut details = MemoryDetails::default();
+    for (name, bytes) in meminfo_entries(meminfo) {
+        let field = match name {
+            "Buffers" => &mut details.buffers_bytes,
+            "Cached" => &mut details.cached_bytes,
+            "Shmem" => &mut details.shmem_bytes,
+            "SReclaimable" => &mut details.slab_reclaimable_bytes,
+            "SUnreclaim" => &mut details.slab_unreclaimable_bytes,
+            "AnonPages" => &mut details.anon_bytes,
+            "Mapped" => &mut details.mapped_bytes,
+            "Dirty" => &mut details.dirty_bytes,
+            "Writeback" => &mut details.writeback_bytes,
+            "Committed_AS" => &mut details.committed_bytes,
+            "CommitLimit" => &mut details.commit_limit_bytes,
+            "HugePages_Total" => &mut details.huge_pages_total,
+            "HugePages_Free" => &mut details.huge_pages_free,
+            "Hugepagesize" => &mut details.huge_page_size_bytes,
+            "AnonHugePages" => &mut details.anon_huge_pages_bytes,
+            _ => continue,
+        };
+        *field = bytes;
+    }
+    details
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMINFO: &str = "\
+MemTotal:       16303428 kB
+Buffers:          213452 kB
+Cached:          4126376 kB
+Shmem:            312644 kB
+SReclaimable:     298340 kB
+SUnreclaim:       140980 kB
+AnonPages:       3356720 kB
+Mapped:           723452 kB
+Dirty:               188 kB
+Writeback:             0 kB
+CommitLimit:    10249524 kB
+Committed_AS:   11233516 kB
+AnonHugePages:    286720 kB
+HugePages_Total:       4
+HugePages_Free:        3
+Hugepagesize:       2048 kB
+Bogus:               abc kB
+no colon here 12 kB
+Empty:
+";
+
+    #[test]
+    fn parses_meminfo() {
+        let details = parse_meminfo(MEMINFO);
+        assert_eq!(details.buffers_bytes, 213452 * 1024);
+        assert_eq!(details.cached_bytes, 4126376 * 1024);
+        assert_eq!(details.shmem_bytes, 312644 * 1024);
+        assert_eq!(details.slab_reclaimable_bytes, 298340 * 1024);
+        assert_eq!(details.slab_unreclaimable_bytes, 140980 * 1024);
+        assert_eq!(details.anon_bytes, 3356720 * 1024);
+        assert_eq!(details.mapped_bytes, 723452 * 1024);
+        assert_eq!(details.dirty_bytes, 188 * 1024);
+        assert_eq!(details.writeback_bytes, 0);
+        assert_eq!(details.commit_limit_bytes, 10249524 * 1024);
+        assert_eq!(details.committed_bytes, 11233516 * 1024);
+        assert_eq!(details.anon_huge_pages_bytes, 286720 * 1024);
+        assert_eq!(details.huge_pages_total, 4);
+        assert_eq!(details.huge_pages_free, 3);
+        assert_eq!(details.huge_page_size_bytes, 2048 * 1024);
+    }
+
+    #[test]
+    fn skips_malformed_meminfo_lines() {
+        let names: Vec<_> = meminfo_entries("Bogus: abc kB\nno colon 12 kB\nEmpty:\nMemFree: 12 kB\n").collect();
+        assert_eq!(names, [("MemFree", 12 * 1024)]);
+    }
+}
@@ -0,0 +1,358 @@
+//! Collectors that read the current state of the machine.
+//!
+//! Each [`Collector`] measures one part of the report and returns the typed
+//! data from [`crate::report`]. [`Sampler`] combines them into a [`Report`].
+
+use chrono::{DateTime, Local, Utc};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::report::{ProcessInfo, Report, Section};
+
+mod auth_log;
+mod block;
+mod cpu;
+mod disk;
+mod diskstats;
+mod memory;
+mod network;
+mod ports;
+mod pressure;
+mod process;
+mod raid;
+mod sockets;
+mod swap;
+mod system;
+mod users;
+mod utmp;
+
+pub use auth_log::SshLogCollector;
+pub use cpu::CpuCollector;
+pub use disk::DiskCollector;
+pub use memory::MemoryCollector;
+pub use network::NetworkCollector;
+pub use ports::ListeningPortCollector;
+pub use pressure::PressureCollector;
+pub use process::ProcessCollector;
+pub use raid::RaidCollector;
+pub use system::SystemInfoCollector;
+pub use users::UsersCollector;
+
+/// Measures one part of the system.
+///
+/// Collectors keep whatever state they need between refreshes, so rates and
+/// CPU usage cover the time since the previous [`refresh`](Collector::refresh).
+pub trait Collector {
+    type Output;
+
+    /// Reads fresh values from the system.
+    fn refresh(&mut self);
+
+    /// Returns the values read by the last refresh.
+    fn collect(&self) -> Self::Output;
+}
+
+/// Keeps the collectors between samples so repeated snapshots only refresh
+/// what changed and CPU usage is measured over the sampling interval.
+pub struct Sampler {
+    cpu: CpuCollector,
+    memory: MemoryCollector,
+    disks: DiskCollector,
+    processes: ProcessCollector,
+    system: SystemInfoCollector,
+    network: NetworkCollector,
+    ports: ListeningPortCollector,
+    users: UsersCollector,
+    ssh: SshLogCollector,
+    pressure: PressureCollector,
+    raid: RaidCollector,
+    primed: bool,
+    sections: Vec<Section>,
+    top: usize,
+    cpu_sample: Duration,
+    details: bool,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self::from_config(&Config::default())
+    }
+
+    /// Takes the sections, top-N count, CPU sample duration and ignore lists
+    /// from the configuration.
+    pub fn from_config(config: &Config) -> Self {
+        let mut ssh = SshLogCollector::new().with_window(config.ssh_window());
+        if let Some(logs) = &config.ssh.logs {
+            ssh = ssh.with_logs(logs.clone());
+        }
+
+        Sampler {
+            cpu: CpuCollector::new(),
+            memory: MemoryCollector::new(),
+            disks: DiskCollector::new()
+                .include_mount_points(config.disk.include_mount_points.clone().unwrap_or_default())
+                .include_fs_types(config.disk.include_fs_types.clone().unwrap_or_default())
+                .ignore_mount_points(config.disk.ignore_mount_points.clone().unwrap_or_default())
+                .ignore_fs_types(config.disk.ignore_fs_types.clone().unwrap_or_default())
+                .with_forecast_window(config.disk_forecast_window()),
+            processes: ProcessCollector::new(),
+            system: SystemInfoCollector::new(),
+            network: NetworkCollector::new()
+                .ignore_interfaces(config.network.ignore_interfaces.clone().unwrap_or_default()),
+            ports: ListeningPortCollector::new(),
+            users: UsersCollector::new()
+                .with_history(config.login_history())
+                .with_failed_login_window(config.failed_login_window())
+                .with_brute_force_threshold(config.brute_force_threshold()),
+            ssh,
+            pressure: PressureCollector::new().with_cgroups(config.pressure.cgroups.clone().unwrap_or_default()),
+            raid: RaidCollector::new(),
+            primed: false,
+            sections: config.sections(),
+            top: config.top(),
+            cpu_sample: config.cpu_sample(),
+            details: true,
+        }
+    }
+
+    /// Only collects the given sections; the others are left as `None`.
+    pub fn with_sections(mut self, sections: &[Section]) -> Self {
+        self.sections = sections.to_vec();
+        self
+    }
+
+    /// Leaves out disk I/O, fill forecasts, login history and failed logins,
+    /// for views that only show disk usage and sessions.
+    pub fn with_details(mut self, details: bool) -> Self {
+        self.disks = self.disks.with_details(details);
+        self.users = self.users.with_details(details);
+        self.details = details;
+        self
+    }
+
+    /// Number of processes kept in the top CPU / memory lists.
+    pub fn with_top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    fn wants(&self, section: Section) -> bool {
+        self.sections.contains(&section)
+    }
+
+    fn wants_processes(&self) -> bool {
+        self.wants(Section::TopCpu) || self.wants(Section::TopMemory)
+    }
+
+    /// Refreshes only the collectors the selected sections read.
+    fn refresh(&mut self) {
+        if self.wants(Section::Cpu) {
+            self.cpu.refresh();
+        }
+        if self.wants(Section::Memory) {
+            self.memory.refresh();
+        }
+        if self.wants_processes() {
+            self.processes.refresh();
+        }
+        // The block device tree shows the mount points of the disk section.
+        if self.wants(Section::Disk) || self.wants(Section::BlockDevices) {
+            self.disks.refresh();
+        }
+        if self.wants(Section::System) {
+            self.system.refresh();
+        }
+        if self.wants(Section::Network) {
+            self.network.refresh();
+        }
+        if self.wants(Section::Ports) {
+            self.ports.refresh();
+        }
+        if self.wants(Section::Ssh) {
+            self.ssh.refresh();
+        }
+        if self.wants(Section::Pressure) {
+            self.pressure.refresh();
+        }
+        if self.wants(Section::Raid) {
+            self.raid.refresh();
+        }
+    }
+
+    pub fn sample(&mut self) -> Report {
+        // CPU usage, swap and disk I/O rates are the difference between two
+        // refreshes, so the very first sample has to wait; later ones use the
+        // time since the previous sample.
+        if !self.primed {
+            self.refresh();
+            if self.wants(Section::Cpu) || self.wants(Section::TopCpu) || self.wants(Section::Memory) || self.wants(Section::Disk) {
+                std::thread::sleep(self.cpu_sample);
+            }
+            self.primed = true;
+        }
+        self.refresh();
+
+        Report {
+            generated_at: Local::now(),
+            hostname: collect_hostname(),
+            cpu: self.wants(Section::Cpu).then(|| self.cpu.collect()),
+            memory: self.wants(Section::Memory).then(|| self.memory.collect()),
+            disks: self.wants(Section::Disk).then(|| self.disks.collect()),
+            disk_io: (self.wants(Section::Disk) && self.details).then(|| self.disks.io()),
+            top_processes_cpu: self.wants(Section::TopCpu).then(|| self.processes.top_by_cpu(self.top)),
+            top_processes_memory: self.wants(Section::TopMemory).then(|| self.processes.top_by_memory(self.top)),
+            system: self.wants(Section::System).then(|| self.system.collect()),
+            network: self.wants(Section::Network).then(|| self.network.collect()),
+            ports: self.wants(Section::Ports).then(|| self.ports.collect()),
+            users: self.wants(Section::Users).then(|| self.users.collect()),
+            ssh: self.wants(Section::Ssh).then(|| self.ssh.collect()),
+            pressure: self.wants(Section::Pressure).then(|| self.pressure.collect()),
+            block_devices: self.wants(Section::BlockDevices).then(|| self.disks.block_devices()),
+            raid: self.wants(Section::Raid).then(|| self.raid.collect()),
+            alerts: None,
+        }
+    }
+
+    /// Usage of each logical core as of the last sample.
+    pub fn per_core_usage(&self) -> Vec<f32> {
+        self.cpu.per_core_usage()
+    }
+
+    /// Every process as of the last sample, unsorted.
+    pub fn processes(&self) -> Vec<ProcessInfo> {
+        self.processes.collect()
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `$HOSTNAME` when the shell exported it, otherwise the kernel's node name;
+/// no process is spawned, so this is cheap enough for every sample.
+fn collect_hostname() -> Option<String> {
+    std::env::var("HOSTNAME").ok().or_else(sysinfo::System::host_name)
+}
+
+fn get_process_user(pid: u32) -> String {
+    // Try to get user from /proc/PID/status
+    if let Ok(status) = fs::read_to_string(format!("/proc/{}/status", pid)) {
+        for line in status.lines() {
+            if line.starts_with("Uid:") {
+                if let Some(uid_str) = line.split_whitespace().nth(1)
+                    && let Ok(uid) = uid_str.parse::<u32>()
+                    && let Some(user) = ::users::get_user_by_uid(uid)
+                {
+                    return user.name().to_string_lossy().to_string();
+                }
+                break;
+            }
+        }
+    }
+    "unknown".to_string()
+}
+
+/// `/proc/self/mountinfo` and `/proc/swaps` write spaces, tabs, newlines and
+/// backslashes in paths as octal escapes.
+fn unescape(field: &str) -> String {
+    field
+        .replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
+}
+
+/// The bracketed entry of sysfs choice lists such as `lzo lzo-rle [lz4] zstd`.
+fn selected(choices: &str) -> Option<String> {
+    let start = choices.find('[')? + 1;
+    let end = start + choices[start..].find(']')?;
+    Some(choices[start..end].to_string())
+}
+
+/// A sysfs or procfs attribute without the trailing newline; `None` when it
+/// cannot be read or is empty.
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?;
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// Start of a window ending now. Windows reaching back before the earliest
+/// representable time, e.g. `"1000000y"`, cover everything.
+fn window_start(window: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(window)
+        .ok()
+        .and_then(|window| Utc::now().checked_sub_signed(window))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total > 0 {
+        (part as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_of_nothing_is_zero() {
+        assert_eq!(percent(1, 4), 25.0);
+        assert_eq!(percent(0, 4), 0.0);
+        assert_eq!(percent(5, 0), 0.0);
+    }
+
+    #[test]
+    fn unescapes_octal_escapes() {
+        assert_eq!(unescape("/mnt/my\\040disk"), "/mnt/my disk");
+        assert_eq!(unescape("a\\011b\\012c"), "a\tb\nc");
+        assert_eq!(unescape("back\\134040slash"), "back\\040slash");
+        assert_eq!(unescape("/plain"), "/plain");
+    }
+
+    #[test]
+    fn selects_bracketed_choice() {
+        assert_eq!(selected("lzo lzo-rle [lz4] zstd").as_deref(), Some("lz4"));
+        assert_eq!(selected("[none] mq-deadline").as_deref(), Some("none"));
+        assert_eq!(selected("lzo lz4"), None);
+        assert_eq!(selected("[unterminated"), None);
+    }
+
+    #[test]
+    fn reads_trimmed_attributes() {
+        let dir = std::env::temp_dir().join(format!("server-stats-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("scheduler"), "mq-deadline\n").unwrap();
+        fs::write(dir.join("empty"), " \n").unwrap();
+
+        assert_eq!(read_trimmed(dir.join("scheduler")).as_deref(), Some("mq-deadline"));
+        assert_eq!(read_trimmed(dir.join("empty")), None);
+        assert_eq!(read_trimmed(dir.join("missing")), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn window_start_saturates() {
+        let start = window_start(Duration::from_secs(60));
+        assert!(Utc::now() - start >= chrono::Duration::seconds(60));
+        assert_eq!(window_start(Duration::from_secs(u64::MAX)), DateTime::<Utc>::MIN_UTC);
+        assert_eq!(window_start(Duration::from_secs(1_000_000 * 365 * 24 * 3600)), DateTime::<Utc>::MIN_UTC);
+    }
+
+    #[test]
+    fn samples_only_the_selected_sections() {
+        let report = Sampler::new().with_sections(&[Section::System]).sample();
+        assert!(report.system.is_some());
+        assert!(report.cpu.is_none());
+        assert!(report.disks.is_none());
+        assert!(report.disk_io.is_none());
+        assert!(report.users.is_none());
+        assert!(report.top_processes_cpu.is_none());
+    }
+}
@@ -0,0 +1,73 @@
+use sysinfo::Networks;
+
+use super::Collector;
+use super::sockets::SocketTables;
+use crate::report::{InterfaceStats, NetworkInfo, SocketStats};
+
+/// Traffic counters of every network interface and open sockets per state.
+pub struct NetworkCollector {
+    networks: Networks,
+    /// Listening sockets and socket counts of the last refresh, `None` when
+    /// `/proc/net` could not be read.
+    listening_ports: Option<usize>,
+    sockets: Option<SocketStats>,
+    ignore_interfaces: Vec<String>,
+}
+
+impl NetworkCollector {
+    pub fn new() -> Self {
+        NetworkCollector {
+            networks: Networks::new(),
+            listening_ports: None,
+            sockets: None,
+            ignore_interfaces: Vec::new(),
+        }
+    }
+
+    /// Leaves out these interfaces, e.g. `lo`.
+    pub fn ignore_interfaces(mut self, interfaces: Vec<String>) -> Self {
+        self.ignore_interfaces = interfaces;
+        self
+    }
+}
+
+impl Default for NetworkCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for NetworkCollector {
+    type Output = NetworkInfo;
+
+    fn refresh(&mut self) {
+        self.networks.refresh(true);
+
+        let sockets = SocketTables::read().ok();
+        self.listening_ports = sockets
+            .as_ref()
+            .map(|sockets| sockets.iter().filter(|socket| socket.is_listening()).count());
+        self.sockets = sockets.map(|sockets| sockets.stats());
+    }
+
+    fn collect(&self) -> NetworkInfo {
+        let mut interfaces: Vec<_> = self
+            .networks
+            .list()
+            .iter()
+            .filter(|(interface_name, _)| !self.ignore_interfaces.contains(interface_name))
+            .map(|(interface_name, network)| InterfaceStats {
+                name: interface_name.clone(),
+                received_bytes: network.total_received(),
+                transmitted_bytes: network.total_transmitted(),
+            })
+            .collect();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+        NetworkInfo {
+            interfaces,
+            listening_ports: self.listening_ports,
+            sockets: self.sockets.clone(),
+        }
+    }
+}
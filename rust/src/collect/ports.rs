@@ -0,0 +1,60 @@
+use std::fs;
+
+use super::sockets::{SocketTables, socket_owners};
+use super::{Collector, get_process_user};
+use crate::report::ListeningPort;
+
+/// Every listening TCP socket and bound UDP socket with the process owning it.
+///
+/// Sockets of other users' processes can only be attributed when running as
+/// root; their owner is left empty otherwise.
+#[derive(Debug, Default)]
+pub struct ListeningPortCollector {
+    ports: Vec<ListeningPort>,
+}
+
+impl ListeningPortCollector {
+    pub fn new() -> Self {
+        ListeningPortCollector::default()
+    }
+}
+
+impl Collector for ListeningPortCollector {
+    type Output = Vec<ListeningPort>;
+
+    fn refresh(&mut self) {
+        let Ok(tables) = SocketTables::read() else {
+            self.ports.clear();
+            return;
+        };
+        let owners = socket_owners();
+
+        self.ports = tables
+            .iter()
+            .filter(|socket| socket.is_listening())
+            .map(|socket| {
+                let pid = owners.get(&socket.inode).copied();
+                ListeningPort {
+                    protocol: socket.protocol,
+                    address: socket.local_address,
+                    port: socket.local_port,
+                    pid,
+                    process: pid.and_then(process_name),
+                    user: pid.map(get_process_user),
+                }
+            })
+            .collect();
+        self.ports.sort_by(|a, b| {
+            (a.protocol, a.port, a.address).cmp(&(b.protocol, b.port, b.address))
+        });
+    }
+
+    fn collect(&self) -> Vec<ListeningPort> {
+        self.ports.clone()
+    }
+}
+
+fn process_name(pid: u32) -> Option<String> {
+    let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(comm.trim_end().to_string())
+}
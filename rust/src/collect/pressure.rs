@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::Path;
+
+use super::Collector;
+use crate::report::{CgroupPressure, PressureInfo, PressureStats, ResourcePressure, StallTime};
+
+/// Pressure Stall Information from `/proc/pressure` and from the `*.pressure`
+/// files of cgroups. Load average counts runnable and I/O-blocked tasks, PSI
+/// measures how much time was actually lost waiting.
+#[derive(Debug)]
+pub struct PressureCollector {
+    cgroups: Vec<String>,
+}
+
+impl PressureCollector {
+    pub fn new() -> Self {
+        PressureCollector { cgroups: Vec::new() }
+    }
+
+    /// Cgroups reported besides the one server-stats runs in, as paths below
+    /// the cgroup v2 mount point (`system.slice/nginx.service`).
+    pub fn with_cgroups(mut self, cgroups: Vec<String>) -> Self {
+        self.cgroups = cgroups;
+        self
+    }
+}
+
+impl Default for PressureCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for PressureCollector {
+    type Output = PressureInfo;
+
+    /// The kernel keeps the averages, every collect reads the files again.
+    fn refresh(&mut self) {}
+
+    fn collect(&self) -> PressureInfo {
+        let system = resource_pressure(Path::new("/proc/pressure"), "");
+
+        let mut cgroups = Vec::new();
+        if let Some(mount) = cgroup2_mount() {
+            let own = own_cgroup();
+            for path in own.iter().chain(&self.cgroups) {
+                let relative = path.trim_matches('/');
+                let path = format!("/{}", relative);
+                let dir = Path::new(&mount).join(relative);
+                // The real root cgroup (no `cgroup.type`) repeats /proc/pressure.
+                if !dir.join("cgroup.type").exists() || cgroups.iter().any(|cgroup: &CgroupPressure| cgroup.path == path) {
+                    continue;
+                }
+                if let Some(pressure) = resource_pressure(&dir, ".pressure") {
+                    cgroups.push(CgroupPressure { path, pressure });
+                }
+            }
+        }
+
+        PressureInfo { system, cgroups }
+    }
+}
+
+/// Reads `cpu`, `memory` and `io` with the given suffix from `dir`; `None`
+/// when none of them exists.
+fn resource_pressure(dir: &Path, suffix: &str) -> Option<ResourcePressure> {
+    let read = |resource: &str| {
+        let contents = fs::read_to_string(dir.join(format!("{}{}", resource, suffix))).ok()?;
+        parse_pressure(&contents)
+    };
+    let pressure = ResourcePressure {
+        cpu: read("cpu"),
+        memory: read("memory"),
+        io: read("io"),
+    };
+    (pressure.cpu.is_some() || pressure.memory.is_some() || pressure.io.is_some()).then_some(pressure)
+}
+
+/// `some avg10=0.00 avg60=0.00 avg300=0.00 total=0` and optionally the
+/// same for `full`.
+fn parse_pressure(contents: &str) -> Option<PressureStats> {
+    let mut some = None;
+    let mut full = None;
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let target = match fields.next() {
+            Some("some") => &mut some,
+            Some("full") => &mut full,
+            _ => continue,
+        };
+        let mut stall = StallTime {
+            avg10: 0.0,
+            avg60: 0.0,
+            avg300: 0.0,
+            total_microseconds: 0,
+        };
+        for field in fields {
+            match field.split_once('=') {
+                Some(("avg10", value)) => stall.avg10 = value.parse().ok()?,
+                Some(("avg60", value)) => stall.avg60 = value.parse().ok()?,
+                Some(("avg300", value)) => stall.avg300 = value.parse().ok()?,
+                Some(("total", value)) => stall.total_microseconds = value.parse().ok()?,
+                _ => {}
+            }
+        }
+        *target = Some(stall);
+    }
+    Some(PressureStats { some: some?, full })
+}
+
+/// Where the cgroup v2 hierarchy is mounted: `/sys/fs/cgroup` on unified
+/// systems, `/sys/fs/cgroup/unified` on hybrid ones.
+fn cgroup2_mount() -> Option<String> {
+    let mounts = fs::read_to_string("/proc/mounts").ok()?;
+    mounts.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let mount_point = fields.nth(1)?;
+        (fields.next()? == "cgroup2").then(|| mount_point.to_string())
+    })
+}
+
+/// The `0::<path>` entry of `/proc/self/cgroup`. Inside a container with its
+/// own cgroup namespace this is `/`, the container itself.
+fn own_cgroup() -> Option<String> {
+    let cgroups = fs::read_to_string("/proc/self/cgroup").ok()?;
+    cgroups.lines().find_map(|line| line.strip_prefix("0::")).map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_some_and_full() {
+        let stats = parse_pressure(
+            "some avg10=1.50 avg60=0.75 avg300=0.10 total=123456\nfull avg10=0.20 avg60=0.05 avg300=0.00 total=4242\n",
+        )
+        .unwrap();
+        assert_eq!(stats.some.avg10, 1.5);
+        assert_eq!(stats.some.avg60, 0.75);
+        assert_eq!(stats.some.avg300, 0.1);
+        assert_eq!(stats.some.total_microseconds, 123456);
+        let full = stats.full.unwrap();
+        assert_eq!(full.avg10, 0.2);
+        assert_eq!(full.total_microseconds, 4242);
+    }
+
+    #[test]
+    fn full_is_optional() {
+        let stats = parse_pressure("some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").unwrap();
+        assert!(stats.full.is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_pressure() {
+        assert!(parse_pressure("").is_none());
+        assert!(parse_pressure("full avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").is_none());
+        assert!(parse_pressure("some avg10=abc avg60=0.00 avg300=0.00 total=0\n").is_none());
+        // Unknown lines and fields are skipped.
+        let stats = parse_pressure("other 1 2 3\nsome avg10=0.50 avg60=0.00 avg300=0.00 total=7 extra=1 junk\n").unwrap();
+        assert_eq!(stats.some.avg10, 0.5);
+        assert_eq!(stats.some.total_microseconds, 7);
+    }
+}
@@ -0,0 +1,74 @@
+use std::cmp::Reverse;
+
+use sysinfo::{MemoryRefreshKind, Process, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use super::{Collector, get_process_user, percent};
+use crate::report::ProcessInfo;
+
+/// Every running process with its CPU and memory usage.
+///
+/// Like [`CpuCollector`](super::CpuCollector), CPU usage is measured between
+/// two refreshes.
+pub struct ProcessCollector {
+    sys: System,
+}
+
+impl ProcessCollector {
+    pub fn new() -> Self {
+        ProcessCollector { sys: System::new() }
+    }
+
+    /// The `n` processes using the most CPU.
+    pub fn top_by_cpu(&self, n: usize) -> Vec<ProcessInfo> {
+        let mut processes: Vec<_> = self.sys.processes().values().collect();
+        processes.sort_by(|a, b| b.cpu_usage().total_cmp(&a.cpu_usage()));
+        self.infos(processes.into_iter().take(n))
+    }
+
+    /// The `n` processes using the most memory.
+    pub fn top_by_memory(&self, n: usize) -> Vec<ProcessInfo> {
+        let mut processes: Vec<_> = self.sys.processes().values().collect();
+        processes.sort_by_key(|p| Reverse(p.memory()));
+        self.infos(processes.into_iter().take(n))
+    }
+
+    fn infos<'a>(&self, processes: impl Iterator<Item = &'a Process>) -> Vec<ProcessInfo> {
+        processes.map(|process| process_info(&self.sys, process)).collect()
+    }
+}
+
+impl Default for ProcessCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for ProcessCollector {
+    /// Every process, unsorted.
+    type Output = Vec<ProcessInfo>;
+
+    fn refresh(&mut self) {
+        // The total is needed for each process's memory share.
+        self.sys.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
+        self.sys.refresh_processes_specifics(ProcessesToUpdate::All, true, process_refresh_kind());
+    }
+
+    fn collect(&self) -> Vec<ProcessInfo> {
+        self.infos(self.sys.processes().values())
+    }
+}
+
+fn process_refresh_kind() -> ProcessRefreshKind {
+    ProcessRefreshKind::nothing().with_cpu().with_memory().without_tasks()
+}
+
+fn process_info(sys: &System, process: &Process) -> ProcessInfo {
+    ProcessInfo {
+        pid: process.pid().as_u32(),
+        user: get_process_user(process.pid().as_u32()),
+        name: process.name().to_string_lossy().to_string(),
+        cpu_percent: process.cpu_usage(),
+        memory_bytes: process.memory(),
+        memory_percent: percent(process.memory(), sys.total_memory()),
+    }
+}
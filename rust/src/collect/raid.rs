@@ -0,0 +1,285 @@
+use std::fs;
+use std::path::Path;
+
+use super::{Collector, read_trimmed};
+use crate::report::{RaidArray, RaidMember, RaidMemberState, RaidSync};
+
+/// Operations `/proc/mdstat` reports progress for.
+const SYNC_ACTIONS: [&str; 5] = ["resync", "recovery", "reshape", "check", "repair"];
+
+/// Health of Linux software RAID (md) arrays from `/proc/mdstat`, with the
+/// array state and degraded count from `/sys/block/mdN/md` where available.
+#[derive(Debug)]
+pub struct RaidCollector;
+
+impl RaidCollector {
+    pub fn new() -> Self {
+        RaidCollector
+    }
+}
+
+impl Default for RaidCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for RaidCollector {
+    type Output = Vec<RaidArray>;
+
+    /// The kernel keeps the state, every collect reads the files again.
+    fn refresh(&mut self) {}
+
+    /// Empty when the md driver is not loaded.
+    fn collect(&self) -> Vec<RaidArray> {
+        let Ok(mdstat) = fs::read_to_string("/proc/mdstat") else {
+            return Vec::new();
+        };
+        let mut arrays = parse_mdstat(&mdstat);
+        for array in &mut arrays {
+            read_sysfs_state(array, Path::new("/sys/block"));
+        }
+        arrays
+    }
+}
+
+/// Takes the state from `md/array_state` (`clean`, `active`, `read-auto`,
+/// ...) and the degraded count from `md/degraded` below `sys_block` when
+/// they exist; sysfs knows about arrays whose status line has no `[n/m]` yet.
+fn read_sysfs_state(array: &mut RaidArray, sys_block: &Path) {
+    let md = sys_block.join(&array.name).join("md");
+    if let Some(state) = read_trimmed(md.join("array_state")) {
+        array.state = state;
+    }
+    if let Some(degraded) = read_trimmed(md.join("degraded")).and_then(|degraded| degraded.parse().ok()) {
+        array.degraded_devices = degraded;
+    }
+}
+
+/// Arrays with the degraded count from their `[n/m]` status, see
+/// [`read_sysfs_state`] for the rest.
+///
+/// ```text
+/// md1 : active raid5 sdd1[4] sdc1[2] sdb1[1](F) sda1[0]
+///       5860268032 blocks super 1.2 level 5, 512k chunk, algorithm 2 [4/3] [U_UU]
+///       [====>................]  recovery = 21.3% (415631872/1953422656) finish=252.5min speed=101466K/sec
+/// ```
+fn parse_mdstat(mdstat: &str) -> Vec<RaidArray> {
+    let mut arrays: Vec<RaidArray> = Vec::new();
+    for line in mdstat.lines() {
+        if let Some((name, description)) = line.split_once(" : ")
+            && name.starts_with("md")
+        {
+            arrays.extend(parse_header(name.trim(), description));
+        } else if let Some(array) = arrays.last_mut() {
+            parse_detail(array, line);
+        }
+    }
+
+    for array in &mut arrays {
+        if let (Some(raid_devices), Some(working)) = (array.raid_devices, array.working_devices) {
+            array.degraded_devices = raid_devices.saturating_sub(working);
+        }
+    }
+    arrays
+}
+
+/// `active raid1 sdb1[1] sda1[0](F)`, `active (auto-read-only) raid1 ...` or
+/// `inactive sdb[0](S)`.
+fn parse_header(name: &str, description: &str) -> Option<RaidArray> {
+    let mut fields = description.split_whitespace().filter(|field| !field.starts_with('('));
+    let state = fields.next()?;
+    let mut fields = fields.peekable();
+    // Inactive arrays list their members right away.
+    let level = fields.next_if(|field| !field.contains('[')).map(String::from);
+
+    Some(RaidArray {
+        name: name.to_string(),
+        level,
+        state: state.to_string(),
+        raid_devices: None,
+        working_devices: None,
+        degraded_devices: 0,
+        members: fields.filter_map(parse_member).collect(),
+        sync: None,
+    })
+}
+
+/// `sda1[0]`, `sdb1[1](F)` or `sdc[2](W)(R)`.
+fn parse_member(field: &str) -> Option<RaidMember> {
+    let (device, rest) = field.split_once('[')?;
+    let (slot, flags) = rest.split_once(']')?;
+    let state = if flags.contains("(F)") {
+        RaidMemberState::Failed
+    } else if flags.contains("(S)") {
+        RaidMemberState::Spare
+    } else if flags.contains("(R)") {
+        RaidMemberState::Replacement
+    } else if flags.contains("(J)") {
+        RaidMemberState::Journal
+    } else if flags.contains("(W)") {
+        RaidMemberState::WriteMostly
+    } else {
+        RaidMemberState::Active
+    };
+    Some(RaidMember {
+        device: device.to_string(),
+        slot: slot.parse().ok(),
+        state,
+    })
+}
+
+/// The block count line with `[n/m]`, and the progress or `resync=DELAYED`
+/// line of a running or queued operation.
+fn parse_detail(array: &mut RaidArray, line: &str) {
+    for field in line.split_whitespace() {
+        if let Some(counts) = field.strip_prefix('[').and_then(|field| field.strip_suffix(']'))
+            && let Some((raid_devices, working)) = counts.split_once('/')
+            && let (Ok(raid_devices), Ok(working)) = (raid_devices.parse(), working.parse())
+        {
+            array.raid_devices = Some(raid_devices);
+            array.working_devices = Some(working);
+        }
+        if let Some((action, _)) = field.split_once('=')
+            && SYNC_ACTIONS.contains(&action)
+        {
+            // `resync=DELAYED` or `resync=PENDING`.
+            array.sync = Some(RaidSync {
+                action: action.to_string(),
+                progress_percent: None,
+                speed_bytes_per_second: None,
+                finish_seconds: None,
+            });
+        }
+    }
+
+    let mut fields = line.split_whitespace();
+    let Some(action) = fields.find(|field| SYNC_ACTIONS.contains(field)) else {
+        return;
+    };
+    if fields.next() != Some("=") {
+        return;
+    }
+    let mut sync = RaidSync {
+        action: action.to_string(),
+        progress_percent: fields.next().and_then(|percent| percent.strip_suffix('%')?.parse().ok()),
+        speed_bytes_per_second: None,
+        finish_seconds: None,
+    };
+    for field in fields {
+        match field.split_once('=') {
+            Some(("finish", minutes)) => {
+                let minutes: Option<f64> = minutes.strip_suffix("min").and_then(|minutes| minutes.parse().ok());
+                sync.finish_seconds = minutes.map(|minutes| (minutes * 60.0) as u64);
+            }
+            Some(("speed", speed)) => {
+                let kilobytes: Option<u64> = speed.strip_suffix("K/sec").and_then(|speed| speed.parse().ok());
+                sync.speed_bytes_per_second = kilobytes.map(|kilobytes| kilobytes * 1024);
+            }
+            _ => {}
+        }
+    }
+    array.sync = Some(sync);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MDSTAT: &str = "\
+Personalities : [raid1] [raid6] [raid5] [raid4]
+md1 : active raid5 sdd1[4] sdc1[2] sdb1[1](F) sda1[0]
+      5860268032 blocks super 1.2 level 5, 512k chunk, algorithm 2 [4/3] [U_UU]
+      [====>................]  recovery = 21.3% (415631872/1953422656) finish=252.5min speed=101466K/sec
+      bitmap: 0/15 pages [0KB], 65536KB chunk
+
+md0 : active raid1 sdf1[1] sde1[0] garbage
+      1048512 blocks super 1.2 [2/2] [UU]
+      \tresync=DELAYED
+
+md127 : inactive sdg[0](S)
+      976630488 blocks super 1.2
+
+md3 : (auto-read-only)
+unused devices: <none>
+";
+
+    #[test]
+    fn parses_degraded_array_in_recovery() {
+        let arrays = parse_mdstat(MDSTAT);
+        let names: Vec<_> = arrays.iter().map(|array| array.name.as_str()).collect();
+        assert_eq!(names, ["md1", "md0", "md127"]);
+
+        let md1 = &arrays[0];
+        assert_eq!(md1.level.as_deref(), Some("raid5"));
+        assert_eq!(md1.state, "active");
+        assert_eq!((md1.raid_devices, md1.working_devices, md1.degraded_devices), (Some(4), Some(3), 1));
+        let members: Vec<_> = md1.members.iter().map(|member| (member.device.as_str(), member.slot, member.state)).collect();
+        assert_eq!(
+            members,
+            [
+                ("sdd1", Some(4), RaidMemberState::Active),
+                ("sdc1", Some(2), RaidMemberState::Active),
+                ("sdb1", Some(1), RaidMemberState::Failed),
+                ("sda1", Some(0), RaidMemberState::Active),
+            ]
+        );
+
+        let sync = md1.sync.as_ref().unwrap();
+        assert_eq!(sync.action, "recovery");
+        assert_eq!(sync.progress_percent, Some(21.3));
+        assert_eq!(sync.speed_bytes_per_second, Some(101466 * 1024));
+        assert_eq!(sync.finish_seconds, Some(15150));
+    }
+
+    #[test]
+    fn parses_delayed_resync_and_inactive_array() {
+        let arrays = parse_mdstat(MDSTAT);
+
+        let md0 = &arrays[1];
+        assert_eq!(md0.members.len(), 2);
+        assert_eq!(md0.degraded_devices, 0);
+        let sync = md0.sync.as_ref().unwrap();
+        assert_eq!(sync.action, "resync");
+        assert_eq!(sync.progress_percent, None);
+
+        let md127 = &arrays[2];
+        assert_eq!(md127.state, "inactive");
+        assert_eq!(md127.level, None);
+        assert_eq!(md127.raid_devices, None);
+        assert_eq!(md127.members[0].state, RaidMemberState::Spare);
+        assert!(md127.sync.is_none());
+    }
+
+    #[test]
+    fn prefers_sysfs_state() {
+        let sys_block = std::env::temp_dir().join(format!("server-stats-raid-test-{}", std::process::id()));
+        for (array, state, degraded) in [("md1", "clean", "2"), ("md127", "inactive", "n/a")] {
+            let md = sys_block.join(array).join("md");
+            fs::create_dir_all(&md).unwrap();
+            fs::write(md.join("array_state"), format!("{}\n", state)).unwrap();
+            fs::write(md.join("degraded"), format!("{}\n", degraded)).unwrap();
+        }
+
+        let mut arrays = parse_mdstat(MDSTAT);
+        for array in &mut arrays {
+            read_sysfs_state(array, &sys_block);
+        }
+        fs::remove_dir_all(&sys_block).unwrap();
+
+        assert_eq!((arrays[0].state.as_str(), arrays[0].degraded_devices), ("clean", 2));
+        // No sysfs entry: the mdstat values stay.
+        assert_eq!((arrays[1].state.as_str(), arrays[1].degraded_devices), ("active", 0));
+        // An unreadable count keeps the one from mdstat.
+        assert_eq!((arrays[2].state.as_str(), arrays[2].degraded_devices), ("inactive", 0));
+    }
+
+    #[test]
+    fn parses_member_flags() {
+        assert_eq!(parse_member("sdc[2](W)(R)").unwrap().state, RaidMemberState::Replacement);
+        assert_eq!(parse_member("nvme0n1p1[3](J)").unwrap().state, RaidMemberState::Journal);
+        assert_eq!(parse_member("sdc[2](W)").unwrap().state, RaidMemberState::WriteMostly);
+        assert!(parse_member("sdc").is_none());
+        assert!(parse_member("sdc[2").is_none());
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::report::{SocketCounts, SocketProtocol, SocketStats, TcpState};
+
+/// One line of `/proc/net/{tcp,tcp6,udp,udp6}`.
+#[derive(Debug, Clone)]
+pub(super) struct SocketEntry {
+    pub protocol: SocketProtocol,
+    pub local_address: IpAddr,
+    pub local_port: u16,
+    /// Kernel `TCP_*` state number; UDP sockets reuse the same numbers.
+    pub state: u8,
+    pub inode: u64,
+}
+
+impl SocketEntry {
+    /// Listening TCP sockets, and UDP sockets that are bound but not connected.
+    pub fn is_listening(&self) -> bool {
+        match self.protocol {
+            SocketProtocol::Tcp => self.state == TcpState::Listen as u8,
+            SocketProtocol::Udp => self.state == TcpState::Close as u8,
+        }
+    }
+}
+
+/// Socket tables per protocol and address family.
+pub(super) struct SocketTables {
+    pub tcp4: Vec<SocketEntry>,
+    pub tcp6: Vec<SocketEntry>,
+    pub udp4: Vec<SocketEntry>,
+    pub udp6: Vec<SocketEntry>,
+}
+
+impl SocketTables {
+    /// Reads the four tables. The IPv6 ones are empty on kernels without IPv6.
+    pub fn read() -> io::Result<Self> {
+        Ok(SocketTables {
+            tcp4: read_table("/proc/net/tcp", SocketProtocol::Tcp)?,
+            tcp6: read_table("/proc/net/tcp6", SocketProtocol::Tcp).unwrap_or_default(),
+            udp4: read_table("/proc/net/udp", SocketProtocol::Udp)?,
+            udp6: read_table("/proc/net/udp6", SocketProtocol::Udp).unwrap_or_default(),
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SocketEntry> {
+        self.tcp4.iter().chain(&self.tcp6).chain(&self.udp4).chain(&self.udp6)
+    }
+
+    pub fn stats(&self) -> SocketStats {
+        SocketStats {
+            ipv4: counts(&self.tcp4, &self.udp4),
+            ipv6: counts(&self.tcp6, &self.udp6),
+        }
+    }
+}
+
+fn counts(tcp: &[SocketEntry], udp: &[SocketEntry]) -> SocketCounts {
+    let mut counts = SocketCounts::default();
+    for entry in tcp {
+        if let Some(state) = TcpState::from_code(entry.state) {
+            *counts.tcp.entry(state).or_default() += 1;
+        }
+    }
+    counts.udp = udp.len();
+    counts
+}
+
+/// Maps socket inodes to the lowest PID holding them, from the
+/// `socket:[inode]` links in `/proc/<pid>/fd`. Processes whose descriptors
+/// cannot be read (other users' without root) are skipped.
+pub(super) fn socket_owners() -> HashMap<u64, u32> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+    let mut pids: Vec<u32> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .collect();
+    // Forked workers share their parent's sockets; report the parent.
+    pids.sort_unstable();
+
+    let mut owners = HashMap::new();
+    for pid in pids {
+        let Ok(fds) = fs::read_dir(format!("/proc/{}/fd", pid)) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Ok(target) = fs::read_link(fd.path())
+                && let Some(inode) = target
+                    .to_str()
+                    .and_then(|target| target.strip_prefix("socket:["))
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|inode| inode.parse().ok())
+            {
+                owners.entry(inode).or_insert(pid);
+            }
+        }
+    }
+    owners
+}
+
+fn read_table(path: &str, protocol: SocketProtocol) -> io::Result<Vec<SocketEntry>> {
+    Ok(parse_table(&fs::read_to_string(path)?, protocol))
+}
+
+fn parse_table(contents: &str, protocol: SocketProtocol) -> Vec<SocketEntry> {
+    // The first line is the column header.
+    contents.lines().skip(1).filter_map(|line| parse_line(line, protocol)).collect()
+}
+
+/// Parses `sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode ...`.
+fn parse_line(line: &str, protocol: SocketProtocol) -> Option<SocketEntry> {
+    let fields: Vec<_> = line.split_whitespace().collect();
+    let (address, port) = fields.get(1)?.split_once(':')?;
+
+    Some(SocketEntry {
+        protocol,
+        local_address: parse_address(address)?,
+        local_port: u16::from_str_radix(port, 16).ok()?,
+        state: u8::from_str_radix(fields.get(3)?, 16).ok()?,
+        inode: fields.get(9)?.parse().ok()?,
+    })
+}
+
+/// Addresses are printed as 32-bit words in host byte order.
+fn parse_address(hex: &str) -> Option<IpAddr> {
+    let word = |i: usize| hex.get(i * 8..i * 8 + 8).and_then(|word| u32::from_str_radix(word, 16).ok());
+
+    match hex.len() {
+        8 => Some(IpAddr::V4(Ipv4Addr::from(word(0)?.to_ne_bytes()))),
+        32 => {
+            let mut bytes = [0u8; 16];
+            for i in 0..4 {
+                bytes[i * 4..i * 4 + 4].copy_from_slice(&word(i)?.to_ne_bytes());
+            }
+            Some(IpAddr::V6(Ipv6Addr::from(bytes)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An address as the kernel prints it: 32-bit words in host byte order.
+    fn hex(address: IpAddr) -> String {
+        let bytes = match address {
+            IpAddr::V4(address) => address.octets().to_vec(),
+            IpAddr::V6(address) => address.octets().to_vec(),
+        };
+        bytes.chunks(4).map(|word| format!("{:08X}", u32::from_ne_bytes(word.try_into().unwrap()))).collect()
+    }
+
+    fn table(lines: &[(IpAddr, u16, u8, u64)]) -> String {
+        let mut table = String::from(
+            "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n",
+        );
+        for (i, (address, port, state, inode)) in lines.iter().enumerate() {
+            table += &format!(
+                "{:4}: {}:{:04X} 00000000:0000 {:02X} 00000000:00000000 00:00000000 00000000  1000        0 {} 1 0000000000000000 100 0 0 10 0\n",
+                i, hex(*address), port, state, inode
+            );
+        }
+        table
+    }
+
+    #[test]
+    fn parses_tcp_table() {
+        let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let contents = table(&[(localhost, 8080, 0x0A, 12345), (IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)), 22, 0x01, 23456)])
+            + "   2: 0100007F\n   3: ZZZZZZZZ:0016 00000000:0000 0A\n";
+
+        let entries = parse_table(&contents, SocketProtocol::Tcp);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].local_address, localhost);
+        assert_eq!(entries[0].local_port, 8080);
+        assert_eq!(entries[0].state, TcpState::Listen as u8);
+        assert_eq!(entries[0].inode, 12345);
+        assert!(entries[0].is_listening());
+        assert_eq!(entries[1].local_address, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)));
+        assert!(!entries[1].is_listening());
+    }
+
+    #[test]
+    fn parses_udp6_table() {
+        let address: IpAddr = "2001:db8::1".parse().unwrap();
+        let entries = parse_table(&table(&[(address, 53, 0x07, 34567), (IpAddr::V6(Ipv6Addr::UNSPECIFIED), 5353, 0x01, 0)]), SocketProtocol::Udp);
+        assert_eq!(entries[0].local_address, address);
+        assert_eq!(entries[0].local_port, 53);
+        assert!(entries[0].is_listening());
+        // Connected UDP sockets are not listening.
+        assert!(!entries[1].is_listening());
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        assert!(parse_address("0100007").is_none());
+        assert!(parse_address("0100007G").is_none());
+        assert!(parse_address("").is_none());
+        assert!(parse_line("   0: 0100007F:1F90 00000000:0000 0A", SocketProtocol::Tcp).is_none());
+    }
+}
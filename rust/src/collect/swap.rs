@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::Path;
+
+use super::{selected, unescape};
+use crate::report::{SwapDevice, ZramStats, ZswapStats};
+
+/// Pages swapped in and out since boot, from `/proc/vmstat`.
+pub(super) fn read_vmstat() -> Option<(u64, u64)> {
+    parse_vmstat(&fs::read_to_string("/proc/vmstat").ok()?)
+}
+
+fn parse_vmstat(vmstat: &str) -> Option<(u64, u64)> {
+    let mut swapped_in = None;
+    let mut swapped_out = None;
+    for line in vmstat.lines() {
+        match line.split_once(' ') {
+            Some(("pswpin", value)) => swapped_in = value.parse().ok(),
+            Some(("pswpout", value)) => swapped_out = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Some((swapped_in?, swapped_out?))
+}
+
+/// Every active swap area from `/proc/swaps`, with compression statistics
+/// for zram devices.
+pub(super) fn read_swaps() -> Vec<SwapDevice> {
+    let Ok(swaps) = fs::read_to_string("/proc/swaps") else {
+        return Vec::new();
+    };
+    let mut devices = parse_swaps(&swaps);
+    for device in &mut devices {
+        device.zram = device.name.strip_prefix("/dev/").filter(|device| device.starts_with("zram")).and_then(zram_stats);
+    }
+    devices
+}
+
+fn parse_swaps(swaps: &str) -> Vec<SwapDevice> {
+    // Filename  Type  Size  Used  Priority, sizes in kB.
+    swaps
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let [name, kind, size, used, priority] = fields[..] else {
+                return None;
+            };
+            Some(SwapDevice {
+                name: unescape(name),
+                kind: kind.to_string(),
+                size_bytes: size.parse::<u64>().ok()? * 1024,
+                used_bytes: used.parse::<u64>().ok()? * 1024,
+                priority: priority.parse().ok()?,
+                zram: None,
+            })
+        })
+        .collect()
+}
+
+/// `mm_stat` of a zram device: original size, compressed size and memory
+/// used, followed by fields newer kernels added.
+fn zram_stats(device: &str) -> Option<ZramStats> {
+    let dir = Path::new("/sys/block").join(device);
+    let mm_stat = fs::read_to_string(dir.join("mm_stat")).ok()?;
+    let mut fields = mm_stat.split_whitespace().map(|field| field.parse::<u64>().ok());
+    let original_bytes = fields.next()??;
+    let compressed_bytes = fields.next()??;
+    let memory_used_bytes = fields.next()??;
+    let compression_ratio = if compressed_bytes > 0 { original_bytes as f64 / compressed_bytes as f64 } else { 0.0 };
+
+    Some(ZramStats {
+        disk_size_bytes: fs::read_to_string(dir.join("disksize")).ok()?.trim().parse().ok()?,
+        original_bytes,
+        compressed_bytes,
+        memory_used_bytes,
+        compression_ratio,
+        algorithm: fs::read_to_string(dir.join("comp_algorithm")).ok().and_then(|algorithms| selected(&algorithms)),
+    })
+}
+
+/// zswap settings from the module parameters, with the pool size and the
+/// amount stored in it from `Zswap` and `Zswapped` of `/proc/meminfo`
+/// (Linux 5.19 and later). `None` when zswap is not built in.
+pub(super) fn zswap_stats(pool_bytes: Option<u64>, stored_bytes: Option<u64>) -> Option<ZswapStats> {
+    let parameters = Path::new("/sys/module/zswap/parameters");
+    let read = |name: &str| fs::read_to_string(parameters.join(name)).ok().map(|value| value.trim().to_string());
+
+    Some(ZswapStats {
+        enabled: read("enabled")? == "Y",
+        compressor: read("compressor"),
+        max_pool_percent: read("max_pool_percent").and_then(|percent| percent.parse().ok()),
+        pool_bytes,
+        stored_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vmstat() {
+        let vmstat = "nr_free_pages 123456\npswpin 12\npswpout 34\nbroken\npswpoutx 99\n";
+        assert_eq!(parse_vmstat(vmstat), Some((12, 34)));
+    }
+
+    #[test]
+    fn parses_swaps() {
+        let swaps = "\
+Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/dev/zram0                              partition\t4194300\t\t102400\t\t100
+/swap\\040file                          file\t\t2097148\t\t0\t\t-2
+/dev/sda2                               partition\tbroken\t\t0\t\t-3
+/dev/sda3                               partition\t1024
+";
+        let devices = parse_swaps(swaps);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].name, "/dev/zram0");
+        assert_eq!(devices[0].kind, "partition");
+        assert_eq!(devices[0].size_bytes, 4194300 * 1024);
+        assert_eq!(devices[0].used_bytes, 102400 * 1024);
+        assert_eq!(devices[0].priority, 100);
+        assert_eq!(devices[1].name, "/swap file");
+        assert_eq!(devices[1].kind, "file");
+        assert_eq!(devices[1].priority, -2);
+    }
+
+    #[test]
+    fn needs_both_swap_counters() {
+        assert_eq!(parse_vmstat("pswpin 12\n"), None);
+        assert_eq!(parse_vmstat("pswpin 12\npswpout -1\n"), None);
+        assert_eq!(parse_vmstat(""), None);
+    }
+}
@@ -0,0 +1,48 @@
+use sysinfo::{CpuRefreshKind, System};
+
+use super::Collector;
+use crate::report::{LoadAverage, SystemInfo};
+
+/// Operating system, uptime and load average.
+pub struct SystemInfoCollector {
+    sys: System,
+}
+
+impl SystemInfoCollector {
+    pub fn new() -> Self {
+        SystemInfoCollector { sys: System::new() }
+    }
+}
+
+impl Default for SystemInfoCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for SystemInfoCollector {
+    type Output = SystemInfo;
+
+    fn refresh(&mut self) {
+        // Load per core needs the number of CPUs.
+        self.sys.refresh_cpu_list(CpuRefreshKind::nothing());
+    }
+
+    fn collect(&self) -> SystemInfo {
+        let load_avg = System::load_average();
+
+        SystemInfo {
+            os_name: System::name(),
+            os_version: System::os_version(),
+            kernel_version: System::kernel_version(),
+            uptime_seconds: System::uptime(),
+            boot_time: chrono::DateTime::from_timestamp(System::boot_time() as i64, 0),
+            load_per_core: load_avg.one / self.sys.cpus().len() as f64,
+            load_average: LoadAverage {
+                one: load_avg.one,
+                five: load_avg.five,
+                fifteen: load_avg.fifteen,
+            },
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+
+use super::{Collector, window_start};
+use super::utmp::{self, RecordType, UtmpRecord};
+use crate::report::{FailedLoginSource, FailedLoginUser, FailedLogins, LoginRecord, Session, UsersInfo};
+
+const DEFAULT_HISTORY: usize = 10;
+const DEFAULT_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_BRUTE_FORCE_THRESHOLD: usize = 10;
+
+/// Logged in sessions from utmp, recent logins from wtmp and failed logins
+/// from btmp.
+#[derive(Debug)]
+pub struct UsersCollector {
+    history: usize,
+    window: Duration,
+    brute_force_threshold: usize,
+    details: bool,
+}
+
+impl UsersCollector {
+    pub fn new() -> Self {
+        UsersCollector {
+            history: DEFAULT_HISTORY,
+            window: DEFAULT_WINDOW,
+            brute_force_threshold: DEFAULT_BRUTE_FORCE_THRESHOLD,
+            details: true,
+        }
+    }
+
+    /// Only failed logins within this long before the sample are analysed.
+    pub fn with_failed_login_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Failed attempts from one source that flag it as brute-forcing.
+    pub fn with_brute_force_threshold(mut self, threshold: usize) -> Self {
+        self.brute_force_threshold = threshold;
+        self
+    }
+
+    /// Whether collects also read wtmp and btmp for the login history and
+    /// failed logins. On by default; without them only
+    /// [`UsersInfo::logged_in`] is filled in.
+    pub fn with_details(mut self, details: bool) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Number of wtmp logins kept in [`UsersInfo::recent_logins`].
+    pub fn with_history(mut self, history: usize) -> Self {
+        self.history = history;
+        self
+    }
+}
+
+impl Default for UsersCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector for UsersCollector {
+    type Output = UsersInfo;
+
+    /// Nothing is cached, every collect reads the files again.
+    fn refresh(&mut self) {}
+
+    fn collect(&self) -> UsersInfo {
+        let logged_in = read_or_empty(utmp::UTMP_PATH).map(|records| sessions(&records));
+        if !self.details {
+            return UsersInfo {
+                logged_in: logged_in.ok(),
+                recent_logins: None,
+                failed_logins: None,
+                failed_logins_error: None,
+            };
+        }
+        let recent_logins = read_or_empty(utmp::WTMP_PATH).map(|records| login_history(&records, self.history));
+
+        // Failed login attempts
+        let (failed_logins, failed_logins_error) = match read_or_empty(utmp::BTMP_PATH) {
+            Ok(records) => (Some(self.failed_logins(&records)), None),
+            Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+                let message = format!("permission denied reading {}, run as root or a member of the utmp group", utmp::BTMP_PATH);
+                (None, Some(message))
+            }
+            Err(err) => (None, Some(format!("cannot read {}: {}", utmp::BTMP_PATH, err))),
+        };
+
+        UsersInfo {
+            logged_in: logged_in.ok(),
+            recent_logins: recent_logins.ok(),
+            failed_logins,
+            failed_logins_error,
+        }
+    }
+}
+
+impl UsersCollector {
+    /// Aggregates the attempts within the window by source and user name.
+    fn failed_logins(&self, records: &[UtmpRecord]) -> FailedLogins {
+        let since = window_start(self.window);
+        let attempts: Vec<_> = records.iter().filter(|record| record.time >= since).collect();
+
+        let mut sources: HashMap<&str, Tally> = HashMap::new();
+        let mut users: HashMap<&str, Tally> = HashMap::new();
+        for record in &attempts {
+            let source = if record.host.is_empty() { "local" } else { record.host.as_str() };
+            sources.entry(source).or_insert_with(|| Tally::new(record.time)).add(record.time, &record.user);
+            users.entry(&record.user).or_insert_with(|| Tally::new(record.time)).add(record.time, source);
+        }
+
+        let mut by_source: Vec<_> = sources
+            .into_iter()
+            .map(|(source, tally)| FailedLoginSource {
+                source: source.to_string(),
+                attempts: tally.attempts,
+                users: tally.distinct.len(),
+                first_seen: tally.first_seen,
+                last_seen: tally.last_seen,
+                brute_force: tally.attempts >= self.brute_force_threshold,
+            })
+            .collect();
+        by_source.sort_by(|a, b| b.attempts.cmp(&a.attempts).then_with(|| a.source.cmp(&b.source)));
+
+        let mut by_user: Vec<_> = users
+            .into_iter()
+            .map(|(user, tally)| FailedLoginUser {
+                user: user.to_string(),
+                attempts: tally.attempts,
+                sources: tally.distinct.len(),
+                first_seen: tally.first_seen,
+                last_seen: tally.last_seen,
+            })
+            .collect();
+        by_user.sort_by(|a, b| b.attempts.cmp(&a.attempts).then_with(|| a.user.cmp(&b.user)));
+
+        FailedLogins {
+            window_seconds: self.window.as_secs(),
+            brute_force_threshold: self.brute_force_threshold,
+            attempts: attempts.len(),
+            by_source,
+            by_user,
+        }
+    }
+}
+
+/// Attempts from one source or against one user name.
+struct Tally<'a> {
+    attempts: usize,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    /// User names tried from a source, or sources trying a user name.
+    distinct: HashSet<&'a str>,
+}
+
+impl<'a> Tally<'a> {
+    fn new(time: DateTime<Utc>) -> Self {
+        Tally {
+            attempts: 0,
+            first_seen: time,
+            last_seen: time,
+            distinct: HashSet::new(),
+        }
+    }
+
+    fn add(&mut self, time: DateTime<Utc>, other: &'a str) {
+        self.attempts += 1;
+        self.first_seen = self.first_seen.min(time);
+        self.last_seen = self.last_seen.max(time);
+        self.distinct.insert(other);
+    }
+}
+
+/// Like `who`, a missing file means nothing was recorded rather than an error.
+fn read_or_empty(path: &str) -> io::Result<Vec<UtmpRecord>> {
+    match utmp::read_records(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        result => result,
+    }
+}
+
+fn sessions(records: &[UtmpRecord]) -> Vec<Session> {
+    let now = SystemTime::now();
+    records
+        .iter()
+        .filter(|record| record.kind == RecordType::UserProcess && !record.user.is_empty())
+        // Entries left behind by a crash, `who` skips them too.
+        .filter(|record| Path::new(&format!("/proc/{}", record.pid)).exists())
+        .map(|record| Session {
+            user: record.user.clone(),
+            tty: record.line.clone(),
+            host: non_empty(&record.host),
+            login_time: record.time,
+            // The terminal's access time is updated on every keystroke.
+            idle_seconds: fs::metadata(format!("/dev/{}", record.line))
+                .and_then(|metadata| metadata.accessed())
+                .ok()
+                .and_then(|accessed| now.duration_since(accessed).ok())
+                .map(|idle| idle.as_secs()),
+            pid: record.pid,
+        })
+        .collect()
+}
+
+/// The `limit` most recent logins, newest first, paired with their logout
+/// the way `last` does it.
+fn login_history(records: &[UtmpRecord], limit: usize) -> Vec<LoginRecord> {
+    let mut logouts = HashMap::new();
+    let mut next_boot = None;
+    let mut history = Vec::new();
+
+    for record in records.iter().rev() {
+        if history.len() >= limit {
+            break;
+        }
+        match record.kind {
+            RecordType::DeadProcess if !record.line.is_empty() => {
+                logouts.insert(record.line.as_str(), record.time);
+            }
+            // Sessions still open at a reboot ended with it.
+            RecordType::BootTime => {
+                logouts.clear();
+                next_boot = Some(record.time);
+            }
+            RecordType::UserProcess if !record.user.is_empty() => history.push(LoginRecord {
+                user: record.user.clone(),
+                tty: record.line.clone(),
+                host: non_empty(&record.host),
+                login_time: record.time,
+                logout_time: logouts.remove(record.line.as_str()).or(next_boot),
+            }),
+            _ => {}
+        }
+    }
+    history
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(kind: RecordType, line: &str, user: &str, host: &str, time: i64) -> UtmpRecord {
+        UtmpRecord {
+            kind,
+            pid: 0,
+            line: line.to_string(),
+            user: user.to_string(),
+            host: host.to_string(),
+            time: DateTime::from_timestamp(time, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn pairs_logins_with_logouts_and_reboots() {
+        let records = [
+            record(RecordType::UserProcess, "tty1", "root", "", 100),
+            record(RecordType::BootTime, "~", "reboot", "", 200),
+            record(RecordType::UserProcess, "pts/0", "alice", "203.0.113.5", 300),
+            record(RecordType::UserProcess, "pts/1", "bob", "", 400),
+            record(RecordType::DeadProcess, "pts/0", "", "", 500),
+            record(RecordType::UserProcess, "pts/0", "carol", "198.51.100.7", 600),
+        ];
+
+        let history = login_history(&records, 10);
+        let users: Vec<_> = history.iter().map(|login| login.user.as_str()).collect();
+        assert_eq!(users, ["carol", "bob", "alice", "root"]);
+        // Still logged in.
+        assert_eq!(history[0].logout_time, None);
+        assert_eq!(history[0].host.as_deref(), Some("198.51.100.7"));
+        assert_eq!(history[1].logout_time, None);
+        assert_eq!(history[1].host, None);
+        assert_eq!(history[2].logout_time, DateTime::from_timestamp(500, 0));
+        // Ended by the reboot.
+        assert_eq!(history[3].logout_time, DateTime::from_timestamp(200, 0));
+
+        assert_eq!(login_history(&records, 2).len(), 2);
+    }
+
+    #[test]
+    fn aggregates_failed_logins_within_the_window() {
+        let now = Utc::now().timestamp();
+        let records = [
+            record(RecordType::Other, "ssh:notty", "root", "198.51.100.7", now - 120),
+            record(RecordType::Other, "ssh:notty", "admin", "198.51.100.7", now - 60),
+            record(RecordType::Other, "ssh:notty", "root", "198.51.100.7", now - 30),
+            record(RecordType::Other, "tty1", "root", "", now - 10),
+            // Outside the window.
+            record(RecordType::Other, "ssh:notty", "root", "203.0.113.5", now - 7200),
+        ];
+        let collector = UsersCollector::new()
+            .with_failed_login_window(Duration::from_secs(3600))
+            .with_brute_force_threshold(3);
+
+        let failed = collector.failed_logins(&records);
+        assert_eq!(failed.attempts, 4);
+        assert_eq!(failed.window_seconds, 3600);
+
+        let sources: Vec<_> = failed.by_source.iter().map(|source| (source.source.as_str(), source.attempts, source.users, source.brute_force)).collect();
+        assert_eq!(sources, [("198.51.100.7", 3, 2, true), ("local", 1, 1, false)]);
+        assert_eq!(failed.by_source[0].first_seen, DateTime::from_timestamp(now - 120, 0).unwrap());
+        assert_eq!(failed.by_source[0].last_seen, DateTime::from_timestamp(now - 30, 0).unwrap());
+
+        let users: Vec<_> = failed.by_user.iter().map(|user| (user.user.as_str(), user.attempts, user.sources)).collect();
+        assert_eq!(users, [("root", 3, 2), ("admin", 1, 1)]);
+    }
+
+    #[test]
+    fn huge_windows_cover_everything() {
+        let collector = UsersCollector::new().with_failed_login_window(Duration::from_secs(u64::MAX));
+        let failed = collector.failed_logins(&[record(RecordType::Other, "ssh:notty", "root", "", 0)]);
+        assert_eq!(failed.attempts, 1);
+    }
+}
@@ -0,0 +1,123 @@
+use std::fs;
+use std::io;
+
+use chrono::{DateTime, Utc};
+
+pub(super) const UTMP_PATH: &str = "/var/run/utmp";
+pub(super) const WTMP_PATH: &str = "/var/log/wtmp";
+pub(super) const BTMP_PATH: &str = "/var/log/btmp";
+
+/// Size of `struct utmp` on Linux with glibc, the same on 32 and 64 bit.
+const RECORD_SIZE: usize = 384;
+
+/// `ut_type` values from `<utmp.h>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RecordType {
+    BootTime,
+    UserProcess,
+    DeadProcess,
+    Other,
+}
+
+/// One `struct utmp` record of utmp, wtmp or btmp.
+#[derive(Debug, Clone)]
+pub(super) struct UtmpRecord {
+    pub kind: RecordType,
+    pub pid: u32,
+    /// Device name without `/dev/`, e.g. `pts/0`.
+    pub line: String,
+    pub user: String,
+    /// Remote host, empty for local logins.
+    pub host: String,
+    pub time: DateTime<Utc>,
+}
+
+/// Reads every record of a utmp-format file, oldest first.
+pub(super) fn read_records(path: &str) -> io::Result<Vec<UtmpRecord>> {
+    Ok(parse_records(&fs::read(path)?))
+}
+
+fn parse_records(data: &[u8]) -> Vec<UtmpRecord> {
+    // A partially written record at the end is ignored.
+    data.chunks_exact(RECORD_SIZE).map(parse_record).collect()
+}
+
+fn parse_record(record: &[u8]) -> UtmpRecord {
+    let i32_at = |offset: usize| i32::from_ne_bytes(record[offset..offset + 4].try_into().expect("4 bytes"));
+
+    let kind = match i16::from_ne_bytes([record[0], record[1]]) {
+        2 => RecordType::BootTime,
+        7 => RecordType::UserProcess,
+        8 => RecordType::DeadProcess,
+        _ => RecordType::Other,
+    };
+
+    UtmpRecord {
+        kind,
+        pid: i32_at(4).max(0) as u32,
+        line: c_string(&record[8..40]),
+        user: c_string(&record[44..76]),
+        host: c_string(&record[76..332]),
+        // `ut_tv.tv_sec` is 32 bit even on 64-bit systems.
+        time: DateTime::from_timestamp(i32_at(340) as u32 as i64, 0).unwrap_or_default(),
+    }
+}
+
+/// Fixed-size fields are NUL padded, and not terminated when full.
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(kind: i16, pid: i32, line: &str, user: &str, host: &str, time: i32) -> Vec<u8> {
+        let mut record = vec![0u8; RECORD_SIZE];
+        record[0..2].copy_from_slice(&kind.to_ne_bytes());
+        record[4..8].copy_from_slice(&pid.to_ne_bytes());
+        record[8..8 + line.len()].copy_from_slice(line.as_bytes());
+        record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        record[76..76 + host.len()].copy_from_slice(host.as_bytes());
+        record[340..344].copy_from_slice(&time.to_ne_bytes());
+        record
+    }
+
+    #[test]
+    fn parses_records() {
+        let mut data = record(2, 0, "~", "reboot", "6.1.0-18-amd64", 1_700_000_000);
+        data.extend(record(7, 4242, "pts/0", "alice", "203.0.113.5", 1_700_000_100));
+        data.extend(record(8, 4242, "pts/0", "", "", 1_700_000_200));
+        data.extend(record(5, -1, "tty1", "LOGIN", "", 1_700_000_000));
+
+        let records = parse_records(&data);
+        let kinds: Vec<_> = records.iter().map(|record| record.kind).collect();
+        assert_eq!(kinds, [RecordType::BootTime, RecordType::UserProcess, RecordType::DeadProcess, RecordType::Other]);
+
+        let login = &records[1];
+        assert_eq!(login.pid, 4242);
+        assert_eq!(login.line, "pts/0");
+        assert_eq!(login.user, "alice");
+        assert_eq!(login.host, "203.0.113.5");
+        assert_eq!(login.time, DateTime::from_timestamp(1_700_000_100, 0).unwrap());
+        assert_eq!(records[3].pid, 0);
+    }
+
+    #[test]
+    fn ignores_partial_record() {
+        let mut data = record(7, 1, "pts/1", "bob", "", 1_700_000_000);
+        data.extend(&record(7, 2, "pts/2", "carol", "", 1_700_000_000)[..100]);
+        let records = parse_records(&data);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].user, "bob");
+    }
+
+    #[test]
+    fn reads_fields_without_terminating_nul() {
+        let user = "a".repeat(32);
+        let records = parse_records(&record(7, 1, "pts/1", &user, "", 0));
+        assert_eq!(records[0].user, user);
+        assert_eq!(c_string(b"\xffab\0cd"), "\u{fffd}ab");
+    }
+}
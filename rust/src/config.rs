@@ -0,0 +1,367 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer};
+
+use crate::render::{Format, Units};
+use crate::report::Section;
+
+/// System-wide configuration file, read first.
+pub const SYSTEM_CONFIG: &str = "/etc/server-stats.toml";
+
+const DEFAULT_TOP: usize = 5;
+const DEFAULT_LOGGED_USERS: usize = 10;
+const DEFAULT_LOGIN_HISTORY: usize = 10;
+const DEFAULT_CPU_SAMPLE: Duration = Duration::from_millis(200);
+const DEFAULT_FAILED_LOGIN_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_BRUTE_FORCE_THRESHOLD: usize = 10;
+const DEFAULT_SSH_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_DISK_FORECAST_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Settings read from the configuration files and `SERVER_STATS_*`
+/// environment variables. Every field is optional so layers can be merged;
+/// the accessors fall back to the built-in defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Sections included in `report` and `watch`.
+    pub sections: Option<Vec<Section>>,
+    pub format: Option<Format>,
+    /// Number of processes in the top CPU / memory lists.
+    #[serde(deserialize_with = "deserialize_count")]
+    pub top: Option<usize>,
+    /// Maximum number of logged in sessions listed.
+    #[serde(deserialize_with = "deserialize_count")]
+    pub logged_users: Option<usize>,
+    /// Number of recent logins read from wtmp.
+    #[serde(deserialize_with = "deserialize_count")]
+    pub login_history: Option<usize>,
+    /// How long CPU usage, swap and disk activity are sampled for a single
+    /// report; raised to sysinfo's minimum CPU update interval.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub cpu_sample: Option<Duration>,
+    pub units: Option<Units>,
+    pub disk: DiskConfig,
+    pub network: NetworkConfig,
+    pub failed_logins: FailedLoginsConfig,
+    pub ssh: SshConfig,
+    pub pressure: PressureConfig,
+    pub check: CheckConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiskConfig {
+    /// Only these mount points are listed.
+    pub include_mount_points: Option<Vec<String>>,
+    pub ignore_mount_points: Option<Vec<String>>,
+    /// Only these filesystem types are listed, pseudo filesystems included.
+    pub include_fs_types: Option<Vec<String>>,
+    /// Left out on top of the built-in pseudo filesystems.
+    pub ignore_fs_types: Option<Vec<String>>,
+    /// How much usage history the fill-rate forecast is fitted to.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub forecast_window: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    pub ignore_interfaces: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FailedLoginsConfig {
+    /// How far back btmp is analysed.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub window: Option<Duration>,
+    /// Failed attempts from one source within the window that flag it as a
+    /// brute-force source.
+    pub brute_force_threshold: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SshConfig {
+    /// How far back the authentication log is summarised.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub window: Option<Duration>,
+    /// Authentication logs to scan, rotations included.
+    pub logs: Option<Vec<PathBuf>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PressureConfig {
+    /// Cgroups whose pressure is reported besides server-stats' own, relative
+    /// to the cgroup v2 mount point.
+    pub cgroups: Option<Vec<String>>,
+}
+
+/// Default thresholds for `check`; command-line flags take precedence.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheckConfig {
+    pub cpu_warning: Option<f64>,
+    pub cpu_critical: Option<f64>,
+    pub memory_warning: Option<f64>,
+    pub memory_critical: Option<f64>,
+    pub swap_warning: Option<f64>,
+    pub swap_critical: Option<f64>,
+    pub disk_warning: Option<f64>,
+    pub disk_critical: Option<f64>,
+    pub load_warning: Option<f64>,
+    pub load_critical: Option<f64>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Env(String, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, err) => write!(f, "cannot read config file {}: {}", path.display(), err),
+            ConfigError::Parse(path, err) => write!(f, "invalid config file {}: {}", path.display(), err),
+            ConfigError::Env(name, err) => write!(f, "invalid value in {}: {}", name, err),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Loads the layered configuration: the system-wide file, the per-user
+    /// file, `explicit` (the `--config` flag) and finally the environment.
+    /// Missing system and user files are skipped, a missing `explicit` file
+    /// is an error.
+    pub fn load(explicit: Option<&Path>) -> Result<Self, ConfigError> {
+        let mut config = Config::default();
+
+        for path in [Some(PathBuf::from(SYSTEM_CONFIG)), user_config_path()].into_iter().flatten() {
+            if path.exists() {
+                config.merge(Config::from_file(&path)?);
+            }
+        }
+
+        if let Some(path) = explicit {
+            config.merge(Config::from_file(path)?);
+        }
+
+        config.merge(Config::from_env()?);
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|err| ConfigError::Read(path.to_path_buf(), err))?;
+        toml::from_str(&contents).map_err(|err| ConfigError::Parse(path.to_path_buf(), err))
+    }
+
+    /// Reads the `SERVER_STATS_*` overrides. Lists are comma separated.
+    fn from_env() -> Result<Self, ConfigError> {
+        let mut config = Config {
+            sections: env_list("SERVER_STATS_SECTIONS")
+                .map(|names| names.iter().map(|name| name.parse()).collect::<Result<_, _>>())
+                .transpose()
+                .map_err(|err| ConfigError::Env("SERVER_STATS_SECTIONS".to_string(), err))?,
+            top: env_count("SERVER_STATS_TOP")?,
+            logged_users: env_count("SERVER_STATS_LOGGED_USERS")?,
+            login_history: env_count("SERVER_STATS_LOGIN_HISTORY")?,
+            ..Config::default()
+        };
+
+        if let Some(value) = env_var("SERVER_STATS_FORMAT") {
+            let format = <Format as clap::ValueEnum>::from_str(&value, true)
+                .map_err(|err| ConfigError::Env("SERVER_STATS_FORMAT".to_string(), err))?;
+            config.format = Some(format);
+        }
+        config.cpu_sample = env_duration("SERVER_STATS_CPU_SAMPLE")?;
+        if let Some(value) = env_var("SERVER_STATS_UNITS") {
+            let units = match value.as_str() {
+                "binary" => Units::Binary,
+                "decimal" => Units::Decimal,
+                _ => {
+                    let err = format!("unknown units '{}', expected binary or decimal", value);
+                    return Err(ConfigError::Env("SERVER_STATS_UNITS".to_string(), err));
+                }
+            };
+            config.units = Some(units);
+        }
+
+        config.disk.include_mount_points = env_list("SERVER_STATS_INCLUDE_MOUNT_POINTS");
+        config.disk.ignore_mount_points = env_list("SERVER_STATS_IGNORE_MOUNT_POINTS");
+        config.disk.include_fs_types = env_list("SERVER_STATS_INCLUDE_FS_TYPES");
+        config.disk.ignore_fs_types = env_list("SERVER_STATS_IGNORE_FS_TYPES");
+        config.disk.forecast_window = env_duration("SERVER_STATS_DISK_FORECAST_WINDOW")?;
+        config.network.ignore_interfaces = env_list("SERVER_STATS_IGNORE_INTERFACES");
+        config.failed_logins.window = env_duration("SERVER_STATS_FAILED_LOGIN_WINDOW")?;
+        config.failed_logins.brute_force_threshold = env_parse("SERVER_STATS_BRUTE_FORCE_THRESHOLD")?;
+        config.ssh.window = env_duration("SERVER_STATS_SSH_WINDOW")?;
+        config.ssh.logs = env_list("SERVER_STATS_SSH_LOGS").map(|logs| logs.into_iter().map(PathBuf::from).collect());
+        config.pressure.cgroups = env_list("SERVER_STATS_PRESSURE_CGROUPS");
+        Ok(config)
+    }
+
+    /// Overrides every setting that `other` defines.
+    fn merge(&mut self, other: Config) {
+        merge(&mut self.sections, other.sections);
+        merge(&mut self.format, other.format);
+        merge(&mut self.top, other.top);
+        merge(&mut self.logged_users, other.logged_users);
+        merge(&mut self.login_history, other.login_history);
+        merge(&mut self.cpu_sample, other.cpu_sample);
+        merge(&mut self.units, other.units);
+
+        merge(&mut self.disk.include_mount_points, other.disk.include_mount_points);
+        merge(&mut self.disk.ignore_mount_points, other.disk.ignore_mount_points);
+        merge(&mut self.disk.include_fs_types, other.disk.include_fs_types);
+        merge(&mut self.disk.ignore_fs_types, other.disk.ignore_fs_types);
+        merge(&mut self.disk.forecast_window, other.disk.forecast_window);
+        merge(&mut self.network.ignore_interfaces, other.network.ignore_interfaces);
+        merge(&mut self.failed_logins.window, other.failed_logins.window);
+        merge(&mut self.failed_logins.brute_force_threshold, other.failed_logins.brute_force_threshold);
+        merge(&mut self.ssh.window, other.ssh.window);
+        merge(&mut self.ssh.logs, other.ssh.logs);
+        merge(&mut self.pressure.cgroups, other.pressure.cgroups);
+
+        let (check, other) = (&mut self.check, other.check);
+        merge(&mut check.cpu_warning, other.cpu_warning);
+        merge(&mut check.cpu_critical, other.cpu_critical);
+        merge(&mut check.memory_warning, other.memory_warning);
+        merge(&mut check.memory_critical, other.memory_critical);
+        merge(&mut check.swap_warning, other.swap_warning);
+        merge(&mut check.swap_critical, other.swap_critical);
+        merge(&mut check.disk_warning, other.disk_warning);
+        merge(&mut check.disk_critical, other.disk_critical);
+        merge(&mut check.load_warning, other.load_warning);
+        merge(&mut check.load_critical, other.load_critical);
+    }
+
+    pub fn sections(&self) -> Vec<Section> {
+        self.sections.clone().unwrap_or_else(|| Section::ALL.to_vec())
+    }
+
+    pub fn format(&self) -> Format {
+        self.format.unwrap_or(Format::Text)
+    }
+
+    pub fn top(&self) -> usize {
+        self.top.unwrap_or(DEFAULT_TOP)
+    }
+
+    pub fn logged_users(&self) -> usize {
+        self.logged_users.unwrap_or(DEFAULT_LOGGED_USERS)
+    }
+
+    pub fn login_history(&self) -> usize {
+        self.login_history.unwrap_or(DEFAULT_LOGIN_HISTORY)
+    }
+
+    pub fn cpu_sample(&self) -> Duration {
+        // Shorter samples yield meaningless CPU usage, as for `watch` intervals.
+        self.cpu_sample.unwrap_or(DEFAULT_CPU_SAMPLE).max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL)
+    }
+
+    pub fn units(&self) -> Units {
+        self.units.unwrap_or_default()
+    }
+
+    pub fn failed_login_window(&self) -> Duration {
+        self.failed_logins.window.unwrap_or(DEFAULT_FAILED_LOGIN_WINDOW)
+    }
+
+    pub fn brute_force_threshold(&self) -> usize {
+        self.failed_logins.brute_force_threshold.unwrap_or(DEFAULT_BRUTE_FORCE_THRESHOLD)
+    }
+
+    pub fn ssh_window(&self) -> Duration {
+        self.ssh.window.unwrap_or(DEFAULT_SSH_WINDOW)
+    }
+
+    pub fn disk_forecast_window(&self) -> Duration {
+        self.disk.forecast_window.unwrap_or(DEFAULT_DISK_FORECAST_WINDOW)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/server-stats/config.toml`, or `~/.config/...` without it.
+fn user_config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("server-stats").join("config.toml"))
+}
+
+fn merge<T>(value: &mut Option<T>, other: Option<T>) {
+    if other.is_some() {
+        *value = other;
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn env_list(name: &str) -> Option<Vec<String>> {
+    env_var(name).map(|value| {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+fn env_parse<T>(name: &str) -> Result<Option<T>, ConfigError>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    env_var(name)
+        .map(|value| value.parse::<T>())
+        .transpose()
+        .map_err(|err| ConfigError::Env(name.to_string(), err.to_string()))
+}
+
+/// Like `--top`, list lengths start at 1.
+fn env_count(name: &str) -> Result<Option<usize>, ConfigError> {
+    match env_parse::<usize>(name)? {
+        Some(0) => Err(ConfigError::Env(name.to_string(), "must be at least 1".to_string())),
+        count => Ok(count),
+    }
+}
+
+fn env_duration(name: &str) -> Result<Option<Duration>, ConfigError> {
+    env_var(name)
+        .map(|value| humantime::parse_duration(&value))
+        .transpose()
+        .map_err(|err| ConfigError::Env(name.to_string(), err.to_string()))
+}
+
+/// Reads a humantime duration such as `"5m"` or `"24h"`; shared with the
+/// `for` of alert rules.
+pub(crate) fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    humantime::parse_duration(&value)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+fn deserialize_count<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match usize::deserialize(deserializer)? {
+        0 => Err(serde::de::Error::custom("must be at least 1")),
+        count => Ok(Some(count)),
+    }
+}
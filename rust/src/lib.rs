@@ -0,0 +1,30 @@
+//! CPU, memory, disk, process, network, user and system statistics as typed
+//! data.
+//!
+//! Each part of the machine is measured by a [`Collector`](collect::Collector)
+//! that returns one of the types in [`report`]. [`Sampler`](collect::Sampler)
+//! runs the collectors for the selected [`Section`](report::Section)s and
+//! builds a [`Report`](report::Report), which the [`render`] module prints as
+//! text, JSON or Prometheus metrics.
+//!
+//! ```no_run
+//! use server_stats::collect::{Collector, CpuCollector, Sampler};
+//! use server_stats::report::Section;
+//!
+//! // A whole report, limited to a few sections.
+//! let report = Sampler::new().with_sections(&[Section::Cpu, Section::Memory]).sample();
+//! println!("{:.1}% used", report.memory.unwrap().used_percent);
+//!
+//! // A single collector; CPU usage needs two refreshes.
+//! let mut cpu = CpuCollector::new();
+//! cpu.refresh();
+//! std::thread::sleep(std::time::Duration::from_millis(200));
+//! cpu.refresh();
+//! println!("{:.1}% busy", cpu.collect().usage_percent);
+//! ```
+
+pub mod alert;
+pub mod collect;
+pub mod config;
+pub mod render;
+pub mod report;
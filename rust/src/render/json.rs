@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+use crate::report::{Report, ReportDelta};
+
+pub fn render(report: &Report) {
+    // The report only holds plain data, so serialization cannot fail.
+    println!("{}", serde_json::to_string_pretty(report).expect("report serializes to JSON"));
+}
+
+#[derive(Serialize)]
+struct WatchSample<'a> {
+    report: &'a Report,
+    delta: Option<&'a ReportDelta>,
+}
+
+/// Prints one sample per line so the stream can be consumed as JSON Lines.
+pub fn render_watch(report: &Report, delta: Option<&ReportDelta>) {
+    let sample = WatchSample { report, delta };
+    println!("{}", serde_json::to_string(&sample).expect("report serializes to JSON"));
+}
@@ -0,0 +1,73 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::report::{Report, ReportDelta};
+
+mod json;
+pub mod prometheus;
+mod text;
+
+/// Output formats understood by `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Text,
+    Json,
+    Prometheus,
+}
+
+/// How byte sizes are scaled for display. `binary` uses powers of 1024 like
+/// `df -h`, `decimal` powers of 1000 like `df -H`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Binary,
+    Decimal,
+}
+
+impl Units {
+    fn base(self) -> f64 {
+        match self {
+            Units::Binary => 1024.0,
+            Units::Decimal => 1000.0,
+        }
+    }
+
+    pub fn gb(self, bytes: u64) -> f64 {
+        bytes as f64 / self.base().powi(3)
+    }
+
+    pub fn mb(self, bytes: f64) -> f64 {
+        bytes / self.base().powi(2)
+    }
+
+    pub fn kb(self, bytes: f64) -> f64 {
+        bytes / self.base()
+    }
+}
+
+/// Settings that only affect the human readable output.
+#[derive(Debug, Clone, Copy)]
+pub struct TextOptions {
+    pub units: Units,
+    /// Maximum number of logged in sessions listed.
+    pub logged_users: usize,
+}
+
+pub fn render(report: &Report, format: Format, options: &TextOptions) {
+    match format {
+        Format::Text => text::render(report, options),
+        Format::Json => json::render(report),
+        Format::Prometheus => print!("{}", prometheus::encode(report)),
+    }
+}
+
+/// Renders one iteration of watch mode.
+pub fn render_watch(report: &Report, delta: Option<&ReportDelta>, format: Format, options: &TextOptions) {
+    match format {
+        Format::Text => text::render_watch(report, delta, options),
+        Format::Json => json::render_watch(report, delta),
+        Format::Prometheus => print!("{}", prometheus::encode(report)),
+    }
+}
@@ -0,0 +1,535 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::report::{BlockDevice, CoreUsage, DiskIo, DiskUsage, RaidMemberState, Report};
+
+const PREFIX: &str = "server_stats";
+
+/// Encodes the report in the Prometheus text exposition format (0.0.4).
+pub fn encode(report: &Report) -> String {
+    let mut out = Metrics::default();
+
+    // CPU
+    if let Some(cpu) = &report.cpu {
+        out.family("cpu_usage_percent", "gauge", "Average CPU usage across all cores.");
+        out.sample("cpu_usage_percent", &[], cpu.usage_percent as f64);
+        out.family("cpu_cores", "gauge", "Number of logical CPU cores.");
+        out.sample("cpu_cores", &[], cpu.cores as f64);
+
+        if cpu.times.is_some() || !cpu.per_core.is_empty() {
+            out.family("cpu_time_percent", "gauge", "Share of CPU time spent in each mode since the previous sample.");
+        }
+        if let Some(times) = &cpu.times {
+            for (mode, percent) in times.modes() {
+                out.sample("cpu_time_percent", &[("cpu", "all"), ("mode", mode)], percent);
+            }
+        }
+        for core in &cpu.per_core {
+            let id = core.core.to_string();
+            for (mode, percent) in core.times.iter().flat_map(|times| times.modes()) {
+                out.sample("cpu_time_percent", &[("cpu", &id), ("mode", mode)], percent);
+            }
+        }
+
+        if cpu.model.is_some() || cpu.vendor.is_some() {
+            out.family("cpu_info", "gauge", "CPU model and vendor, always 1.");
+            let model = cpu.model.as_deref().unwrap_or("");
+            let vendor = cpu.vendor.as_deref().unwrap_or("");
+            out.sample("cpu_info", &[("model", model), ("vendor", vendor)], 1.0);
+        }
+
+        out.family("cpu_core_usage_percent", "gauge", "CPU usage of each logical core.");
+        for core in &cpu.per_core {
+            out.sample("cpu_core_usage_percent", &[("cpu", &core.core.to_string())], core.usage_percent as f64);
+        }
+        core_frequencies(&mut out, &cpu.per_core, "cpu_frequency_hertz", "Current frequency of each logical core.", |core| {
+            core.frequency_mhz
+        });
+        core_frequencies(&mut out, &cpu.per_core, "cpu_frequency_min_hertz", "Minimum frequency of each logical core.", |core| {
+            core.min_frequency_mhz
+        });
+        core_frequencies(&mut out, &cpu.per_core, "cpu_frequency_max_hertz", "Maximum frequency of each logical core.", |core| {
+            core.max_frequency_mhz
+        });
+        if cpu.per_core.iter().any(|core| core.governor.is_some()) {
+            out.family("cpu_scaling_governor", "gauge", "cpufreq scaling governor of each logical core, always 1.");
+        }
+        for core in &cpu.per_core {
+            if let Some(governor) = &core.governor {
+                out.sample("cpu_scaling_governor", &[("cpu", &core.core.to_string()), ("governor", governor)], 1.0);
+            }
+        }
+    }
+
+    // Memory
+    if let Some(memory) = &report.memory {
+        out.family("memory_total_bytes", "gauge", "Total physical memory in bytes.");
+        out.sample("memory_total_bytes", &[], memory.total_bytes as f64);
+        out.family("memory_used_bytes", "gauge", "Used physical memory in bytes.");
+        out.sample("memory_used_bytes", &[], memory.used_bytes as f64);
+        out.family("memory_available_bytes", "gauge", "Available physical memory in bytes.");
+        out.sample("memory_available_bytes", &[], memory.available_bytes as f64);
+
+        let (swap_total, swap_used) = memory
+            .swap
+            .as_ref()
+            .map_or((0, 0), |swap| (swap.total_bytes, swap.used_bytes));
+        out.family("swap_total_bytes", "gauge", "Total swap space in bytes.");
+        out.sample("swap_total_bytes", &[], swap_total as f64);
+        out.family("swap_used_bytes", "gauge", "Used swap space in bytes.");
+        out.sample("swap_used_bytes", &[], swap_used as f64);
+
+        if let Some(swap) = &memory.swap {
+            if let (Some(swapped_in), Some(swapped_out)) = (swap.swapped_in_pages, swap.swapped_out_pages) {
+                out.family("swap_pages_total", "counter", "Pages swapped in and out since boot.");
+                out.sample("swap_pages_total", &[("direction", "in")], swapped_in as f64);
+                out.sample("swap_pages_total", &[("direction", "out")], swapped_out as f64);
+            }
+
+            if !swap.devices.is_empty() {
+                out.family("swap_device_size_bytes", "gauge", "Size of each swap device or file.");
+                for device in &swap.devices {
+                    out.sample("swap_device_size_bytes", &[("device", &device.name), ("type", &device.kind)], device.size_bytes as f64);
+                }
+                out.family("swap_device_used_bytes", "gauge", "Used space of each swap device or file.");
+                for device in &swap.devices {
+                    out.sample("swap_device_used_bytes", &[("device", &device.name), ("type", &device.kind)], device.used_bytes as f64);
+                }
+            }
+
+            let zram: Vec<_> = swap.devices.iter().filter_map(|device| Some((device.name.as_str(), device.zram.as_ref()?))).collect();
+            if !zram.is_empty() {
+                out.family("zram_original_bytes", "gauge", "Data stored in zram before compression.");
+                for (device, zram) in &zram {
+                    out.sample("zram_original_bytes", &[("device", device)], zram.original_bytes as f64);
+                }
+                out.family("zram_compressed_bytes", "gauge", "Data stored in zram after compression.");
+                for (device, zram) in &zram {
+                    out.sample("zram_compressed_bytes", &[("device", device)], zram.compressed_bytes as f64);
+                }
+                out.family("zram_memory_used_bytes", "gauge", "RAM used by zram, including overhead.");
+                for (device, zram) in &zram {
+                    out.sample("zram_memory_used_bytes", &[("device", device)], zram.memory_used_bytes as f64);
+                }
+            }
+
+            if let Some(zswap) = &swap.zswap {
+                out.family("zswap_enabled", "gauge", "Whether zswap is enabled.");
+                out.sample("zswap_enabled", &[], if zswap.enabled { 1.0 } else { 0.0 });
+                if let (Some(pool), Some(stored)) = (zswap.pool_bytes, zswap.stored_bytes) {
+                    out.family("zswap_pool_bytes", "gauge", "RAM used by the zswap pool.");
+                    out.sample("zswap_pool_bytes", &[], pool as f64);
+                    out.family("zswap_stored_bytes", "gauge", "Data stored in zswap before compression.");
+                    out.sample("zswap_stored_bytes", &[], stored as f64);
+                }
+            }
+        }
+
+        if let Some(details) = &memory.details {
+            out.family("memory_detail_bytes", "gauge", "Memory by use from /proc/meminfo.");
+            for (kind, bytes) in details.bytes() {
+                out.sample("memory_detail_bytes", &[("kind", kind)], bytes as f64);
+            }
+            out.family("memory_huge_pages", "gauge", "Persistent huge pages.");
+            out.sample("memory_huge_pages", &[("state", "total")], details.huge_pages_total as f64);
+            out.sample("memory_huge_pages", &[("state", "free")], details.huge_pages_free as f64);
+            out.family("memory_huge_page_size_bytes", "gauge", "Size of a persistent huge page.");
+            out.sample("memory_huge_page_size_bytes", &[], details.huge_page_size_bytes as f64);
+        }
+    }
+
+    // Disks
+    if let Some(disks) = &report.disks {
+        out.family("filesystem_size_bytes", "gauge", "Filesystem size in bytes.");
+        for disk in disks {
+            let labels = filesystem_labels(disk);
+            out.sample("filesystem_size_bytes", &labels, disk.total_bytes as f64);
+        }
+        out.family("filesystem_used_bytes", "gauge", "Filesystem space used in bytes.");
+        for disk in disks {
+            let labels = filesystem_labels(disk);
+            out.sample("filesystem_used_bytes", &labels, disk.used_bytes as f64);
+        }
+        out.family("filesystem_avail_bytes", "gauge", "Filesystem space available in bytes.");
+        for disk in disks {
+            let labels = filesystem_labels(disk);
+            out.sample("filesystem_avail_bytes", &labels, disk.available_bytes as f64);
+        }
+        out.family("filesystem_readonly", "gauge", "Whether the filesystem is mounted read-only.");
+        for disk in disks {
+            out.sample("filesystem_readonly", &filesystem_labels(disk), if disk.read_only { 1.0 } else { 0.0 });
+        }
+        if disks.iter().any(|disk| disk.inodes.is_some()) {
+            out.family("filesystem_files", "gauge", "Total inodes of the filesystem.");
+            for disk in disks {
+                if let Some(inodes) = &disk.inodes {
+                    out.sample("filesystem_files", &filesystem_labels(disk), inodes.total as f64);
+                }
+            }
+            out.family("filesystem_files_used", "gauge", "Used inodes of the filesystem.");
+            for disk in disks {
+                if let Some(inodes) = &disk.inodes {
+                    out.sample("filesystem_files_used", &filesystem_labels(disk), inodes.used as f64);
+                }
+            }
+        }
+    }
+
+    // Fill-rate forecasts, once enough samples have been taken.
+    if let Some(disks) = &report.disks
+        && disks.iter().any(|disk| disk.forecast.is_some())
+    {
+        out.family("filesystem_growth_bytes_per_second", "gauge", "Growth of used space fitted over the forecast window.");
+        for disk in disks {
+            if let Some(forecast) = &disk.forecast {
+                out.sample("filesystem_growth_bytes_per_second", &filesystem_labels(disk), forecast.growth_bytes_per_second);
+            }
+        }
+        out.family("filesystem_forecast_window_seconds", "gauge", "Time span of the samples the growth was fitted to.");
+        for disk in disks {
+            if let Some(forecast) = &disk.forecast {
+                out.sample("filesystem_forecast_window_seconds", &filesystem_labels(disk), forecast.window_seconds as f64);
+            }
+        }
+        if disks.iter().any(|disk| disk.forecast.is_some_and(|forecast| forecast.seconds_until_full.is_some())) {
+            out.family("filesystem_seconds_until_full", "gauge", "Time until the filesystem is full at its current growth rate.");
+        }
+        for disk in disks {
+            if let Some(seconds) = disk.forecast.and_then(|forecast| forecast.seconds_until_full) {
+                out.sample("filesystem_seconds_until_full", &filesystem_labels(disk), seconds as f64);
+            }
+        }
+    }
+
+    // Disk I/O, named after node_exporter's node_disk_* metrics.
+    if let Some(devices) = &report.disk_io {
+        disk_io(&mut out, devices, "disk_reads_completed_total", "counter", "Reads completed by the block device.", |device| {
+            device.reads_completed as f64
+        });
+        disk_io(&mut out, devices, "disk_writes_completed_total", "counter", "Writes completed by the block device.", |device| {
+            device.writes_completed as f64
+        });
+        disk_io(&mut out, devices, "disk_read_bytes_total", "counter", "Bytes read from the block device.", |device| {
+            device.read_bytes as f64
+        });
+        disk_io(&mut out, devices, "disk_written_bytes_total", "counter", "Bytes written to the block device.", |device| {
+            device.written_bytes as f64
+        });
+        disk_io(&mut out, devices, "disk_read_time_seconds_total", "counter", "Time spent on reads.", |device| {
+            device.read_time_ms as f64 / 1000.0
+        });
+        disk_io(&mut out, devices, "disk_write_time_seconds_total", "counter", "Time spent on writes.", |device| {
+            device.write_time_ms as f64 / 1000.0
+        });
+        disk_io(&mut out, devices, "disk_io_time_seconds_total", "counter", "Time the block device had I/O in flight.", |device| {
+            device.io_time_ms as f64 / 1000.0
+        });
+        disk_io(&mut out, devices, "disk_io_time_weighted_seconds_total", "counter", "I/O time weighted by the requests in flight.", |device| {
+            device.weighted_io_time_ms as f64 / 1000.0
+        });
+        disk_io(&mut out, devices, "disk_io_now", "gauge", "Requests currently in flight on the block device.", |device| {
+            device.in_flight as f64
+        });
+
+        // Mounts come and go, so they are kept out of the counters' labels.
+        if devices.iter().any(|device| !device.mount_points.is_empty()) {
+            out.family("disk_mount_info", "gauge", "Mount point backed by the block device, always 1.");
+        }
+        for device in devices {
+            for mount_point in &device.mount_points {
+                out.sample("disk_mount_info", &[("device", &device.device), ("mountpoint", mount_point)], 1.0);
+            }
+        }
+    }
+
+    // Block device tree, one series per device and one per parent link.
+    if let Some(roots) = &report.block_devices {
+        let mut devices = Vec::new();
+        let mut links = Vec::new();
+        flatten_block_devices(roots, None, &mut devices, &mut links);
+
+        out.family("block_device_size_bytes", "gauge", "Size of the block device.");
+        for device in &devices {
+            out.sample("block_device_size_bytes", &[("device", &device.name), ("type", &device.kind)], device.size_bytes as f64);
+        }
+        out.family("block_device_rotational", "gauge", "Whether the kernel considers the block device rotational.");
+        for device in &devices {
+            out.sample("block_device_rotational", &[("device", &device.name)], if device.rotational { 1.0 } else { 0.0 });
+        }
+        out.family("block_device_readonly", "gauge", "Whether the block device is read-only.");
+        for device in &devices {
+            out.sample("block_device_readonly", &[("device", &device.name)], if device.read_only { 1.0 } else { 0.0 });
+        }
+        if !links.is_empty() {
+            out.family("block_device_parent", "gauge", "Partition or holder relationship between block devices, always 1.");
+        }
+        for (device, parent) in &links {
+            out.sample("block_device_parent", &[("device", device), ("parent", parent)], 1.0);
+        }
+    }
+
+    // Software RAID
+    if let Some(arrays) = &report.raid
+        && !arrays.is_empty()
+    {
+        out.family("raid_info", "gauge", "Level and state of the md array, always 1.");
+        for array in arrays {
+            let level = array.level.as_deref().unwrap_or("");
+            out.sample("raid_info", &[("device", &array.name), ("level", level), ("state", &array.state)], 1.0);
+        }
+        out.family("raid_degraded", "gauge", "Whether the md array is degraded or inactive.");
+        for array in arrays {
+            out.sample("raid_degraded", &[("device", &array.name)], if array.is_degraded() { 1.0 } else { 0.0 });
+        }
+        out.family("raid_degraded_devices", "gauge", "Missing or failed devices the md array runs without.");
+        for array in arrays {
+            out.sample("raid_degraded_devices", &[("device", &array.name)], array.degraded_devices as f64);
+        }
+        out.family("raid_required_devices", "gauge", "Number of devices the md array is built for.");
+        for array in arrays {
+            if let Some(raid_devices) = array.raid_devices {
+                out.sample("raid_required_devices", &[("device", &array.name)], raid_devices as f64);
+            }
+        }
+        out.family("raid_member_devices", "gauge", "Members of the md array by state.");
+        for array in arrays {
+            for state in [RaidMemberState::Active, RaidMemberState::Failed, RaidMemberState::Spare] {
+                let count = array.members.iter().filter(|member| member.state == state).count();
+                out.sample("raid_member_devices", &[("device", &array.name), ("state", state.label())], count as f64);
+            }
+        }
+        if arrays.iter().any(|array| array.sync.is_some()) {
+            out.family("raid_sync_progress_percent", "gauge", "Progress of the running resync, recovery, reshape or check.");
+        }
+        for array in arrays {
+            if let Some(sync) = &array.sync {
+                let progress = sync.progress_percent.unwrap_or(0.0);
+                out.sample("raid_sync_progress_percent", &[("device", &array.name), ("action", &sync.action)], progress);
+            }
+        }
+    }
+
+    // Network
+    if let Some(network) = &report.network {
+        out.family("network_receive_bytes_total", "counter", "Bytes received per network interface.");
+        for interface in &network.interfaces {
+            out.sample("network_receive_bytes_total", &[("interface", &interface.name)], interface.received_bytes as f64);
+        }
+        out.family("network_transmit_bytes_total", "counter", "Bytes transmitted per network interface.");
+        for interface in &network.interfaces {
+            out.sample("network_transmit_bytes_total", &[("interface", &interface.name)], interface.transmitted_bytes as f64);
+        }
+        if let Some(listening_ports) = network.listening_ports {
+            out.family("listening_ports", "gauge", "Number of listening sockets.");
+            out.sample("listening_ports", &[], listening_ports as f64);
+        }
+        if let Some(sockets) = &network.sockets {
+            out.family("tcp_sockets", "gauge", "Open TCP sockets per address family and state.");
+            for (family, counts) in [("ipv4", &sockets.ipv4), ("ipv6", &sockets.ipv6)] {
+                for (state, count) in &counts.tcp {
+                    out.sample("tcp_sockets", &[("family", family), ("state", state.label())], *count as f64);
+                }
+            }
+            out.family("udp_sockets", "gauge", "Open UDP sockets per address family.");
+            out.sample("udp_sockets", &[("family", "ipv4")], sockets.ipv4.udp as f64);
+            out.sample("udp_sockets", &[("family", "ipv6")], sockets.ipv6.udp as f64);
+        }
+    }
+
+    // Listening ports
+    if let Some(ports) = &report.ports {
+        out.family("listening_port", "gauge", "Listening TCP and bound UDP sockets with their owner, always 1.");
+        // No PID label: it changes on every restart of the service. Sockets
+        // sharing a port (SO_REUSEPORT workers) are written once, since
+        // Prometheus rejects a scrape with duplicate series.
+        let mut written = HashSet::new();
+        for port in ports {
+            let address = port.address.to_string();
+            let number = port.port.to_string();
+            let labels = [
+                ("protocol", port.protocol.label()),
+                ("address", address.as_str()),
+                ("port", number.as_str()),
+                ("process", port.process.as_deref().unwrap_or("")),
+                ("user", port.user.as_deref().unwrap_or("")),
+            ];
+            if written.insert(labels.map(|(_, value)| value.to_string())) {
+                out.sample("listening_port", &labels, 1.0);
+            }
+        }
+    }
+
+    // Additional system information
+    if let Some(system) = &report.system {
+        out.family("os_info", "gauge", "Operating system and kernel version, always 1.");
+        out.sample(
+            "os_info",
+            &[
+                ("name", system.os_name.as_deref().unwrap_or("Unknown")),
+                ("version", system.os_version.as_deref().unwrap_or("Unknown")),
+                ("kernel", system.kernel_version.as_deref().unwrap_or("Unknown")),
+            ],
+            1.0,
+        );
+        out.family("uptime_seconds", "gauge", "System uptime in seconds.");
+        out.sample("uptime_seconds", &[], system.uptime_seconds as f64);
+        if let Some(boot_time) = system.boot_time {
+            out.family("boot_time_seconds", "gauge", "System boot time in seconds since the Unix epoch.");
+            out.sample("boot_time_seconds", &[], boot_time.timestamp() as f64);
+        }
+        out.family("load1", "gauge", "1 minute load average.");
+        out.sample("load1", &[], system.load_average.one);
+        out.family("load5", "gauge", "5 minute load average.");
+        out.sample("load5", &[], system.load_average.five);
+        out.family("load15", "gauge", "15 minute load average.");
+        out.sample("load15", &[], system.load_average.fifteen);
+        out.family("load_per_core", "gauge", "1 minute load average divided by the number of cores.");
+        out.sample("load_per_core", &[], system.load_per_core);
+    }
+
+    if let Some(logged_in) = report.users.as_ref().and_then(|users| users.logged_in.as_ref()) {
+        out.family("logged_in_users", "gauge", "Number of logged in user sessions.");
+        out.sample("logged_in_users", &[], logged_in.len() as f64);
+    }
+
+    if let Some(failed_logins) = report.users.as_ref().and_then(|users| users.failed_logins.as_ref()) {
+        out.family("failed_login_attempts", "gauge", "Failed login attempts in btmp within the configured window.");
+        out.sample("failed_login_attempts", &[], failed_logins.attempts as f64);
+        out.family("failed_login_sources", "gauge", "Sources of failed logins within the window.");
+        out.sample("failed_login_sources", &[], failed_logins.by_source.len() as f64);
+        let brute_force = failed_logins.by_source.iter().filter(|source| source.brute_force).count();
+        out.family("brute_force_sources", "gauge", "Sources at or above the brute-force threshold within the window.");
+        out.sample("brute_force_sources", &[], brute_force as f64);
+    }
+
+    // SSH logins
+    if let Some(ssh) = &report.ssh {
+        out.family("ssh_events", "gauge", "sshd events in the authentication log within the configured window.");
+        out.sample("ssh_events", &[("event", "accepted")], ssh.accepted as f64);
+        out.sample("ssh_events", &[("event", "failed")], ssh.failed as f64);
+        out.sample("ssh_events", &[("event", "invalid_user")], ssh.invalid_users as f64);
+        out.sample("ssh_events", &[("event", "disconnect")], ssh.disconnects as f64);
+    }
+
+    // Pressure stall information
+    if let Some(pressure) = &report.pressure {
+        let mut stalls = Vec::new();
+        for (scope, resources) in pressure.scopes() {
+            for (resource, stats) in resources.resources() {
+                stalls.push((scope, resource, "some", stats.some));
+                if let Some(full) = stats.full {
+                    stalls.push((scope, resource, "full", full));
+                }
+            }
+        }
+
+        if !stalls.is_empty() {
+            out.family("pressure_percent", "gauge", "Share of time tasks were stalled on the resource, averaged over the window.");
+            for (scope, resource, kind, stall) in &stalls {
+                for (window, value) in [("10s", stall.avg10), ("60s", stall.avg60), ("300s", stall.avg300)] {
+                    out.sample("pressure_percent", &[("scope", scope), ("resource", resource), ("kind", kind), ("window", window)], value);
+                }
+            }
+            out.family("pressure_stalled_seconds_total", "counter", "Total time tasks were stalled on the resource.");
+            for (scope, resource, kind, stall) in &stalls {
+                let seconds = stall.total_microseconds as f64 / 1e6;
+                out.sample("pressure_stalled_seconds_total", &[("scope", scope), ("resource", resource), ("kind", kind)], seconds);
+            }
+        }
+    }
+
+    // Alerts
+    if let Some(alerts) = &report.alerts {
+        out.family("alert", "gauge", "Pending or firing alert rules, always 1.");
+        for alert in alerts {
+            out.sample(
+                "alert",
+                &[
+                    ("rule", alert.rule.as_str()),
+                    ("severity", &alert.severity.label().to_lowercase()),
+                    ("state", alert.state.label()),
+                    ("instance", alert.instance.as_deref().unwrap_or("")),
+                ],
+                1.0,
+            );
+        }
+    }
+
+    out.0
+}
+
+#[derive(Default)]
+struct Metrics(String);
+
+impl Metrics {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {PREFIX}_{name} {help}");
+        let _ = writeln!(self.0, "# TYPE {PREFIX}_{name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let _ = write!(self.0, "{PREFIX}_{name}");
+        if !labels.is_empty() {
+            let labels: Vec<_> = labels
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            let _ = write!(self.0, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.0, " {}", value);
+    }
+}
+
+fn filesystem_labels(disk: &DiskUsage) -> [(&str, &str); 3] {
+    [("device", &disk.filesystem), ("mountpoint", &disk.mount_point), ("fstype", &disk.fs_type)]
+}
+
+/// One sample per block device; `disk_mount_info` maps them to mount points.
+fn disk_io(out: &mut Metrics, devices: &[DiskIo], name: &str, kind: &str, help: &str, value: impl Fn(&DiskIo) -> f64) {
+    if devices.is_empty() {
+        return;
+    }
+    out.family(name, kind, help);
+    for device in devices {
+        out.sample(name, &[("device", &device.device)], value(device));
+    }
+}
+
+/// Every device of the tree once, and each (device, parent) link. Volumes
+/// spanning several devices appear under each of them in the tree.
+fn flatten_block_devices<'a>(
+    devices: &'a [BlockDevice],
+    parent: Option<&'a str>,
+    flat: &mut Vec<&'a BlockDevice>,
+    links: &mut Vec<(&'a str, &'a str)>,
+) {
+    for device in devices {
+        if let Some(parent) = parent {
+            links.push((&device.name, parent));
+        }
+        if !flat.iter().any(|seen| seen.name == device.name) {
+            flat.push(device);
+            flatten_block_devices(&device.children, Some(&device.name), flat, links);
+        }
+    }
+}
+
+/// One gauge per core in hertz, skipped entirely when no core reports it.
+fn core_frequencies(out: &mut Metrics, cores: &[CoreUsage], name: &str, help: &str, mhz: impl Fn(&CoreUsage) -> Option<u64>) {
+    if cores.iter().all(|core| mhz(core).is_none()) {
+        return;
+    }
+    out.family(name, "gauge", help);
+    for core in cores {
+        if let Some(mhz) = mhz(core) {
+            out.sample(name, &[("cpu", &core.core.to_string())], mhz as f64 * 1e6);
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,796 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::alert::Alert;
+use crate::render::{TextOptions, Units};
+use crate::report::{
+    BlockDevice, CpuTimes, CpuUsage, DiskIo, DiskUsage, FailedLogins, ListeningPort, MemoryDetails, MemoryUsage,
+    NetworkInfo, PressureInfo, PressureStats, ProcessInfo, RaidArray, RaidMemberState, Report, ReportDelta,
+    SocketCounts, SshActivity, SshSummary, StallTime, SwapUsage, SystemInfo, UsersInfo,
+};
+
+/// Rows per failed-login and SSH table; scans can come from thousands of addresses.
+const FAILED_LOGIN_ROWS: usize = 10;
+/// Cells of the per-core usage bars.
+const CORE_BAR_WIDTH: usize = 20;
+
+pub fn render(report: &Report, options: &TextOptions) {
+    print_title(report);
+    print_sections(report, options);
+    print_footer();
+}
+
+/// Redraws the report in place, followed by the changes since the previous sample.
+pub fn render_watch(report: &Report, delta: Option<&ReportDelta>, options: &TextOptions) {
+    // Move the cursor home and clear the screen.
+    print!("\x1b[H\x1b[2J");
+    print_title(report);
+    print_sections(report, options);
+    print_delta(delta, options.units);
+    print_footer();
+}
+
+fn print_title(report: &Report) {
+    println!("=========================================");
+    println!("       SERVER PERFORMANCE STATS");
+    println!("=========================================");
+    println!("Generated on: {}", report.generated_at.format("%Y-%m-%d %H:%M:%S"));
+
+    if let Some(hostname) = &report.hostname {
+        println!("Hostname: {}", hostname);
+    }
+
+    println!("=========================================");
+}
+
+fn print_sections(report: &Report, options: &TextOptions) {
+    let units = options.units;
+
+    // CPU Usage
+    if let Some(cpu) = &report.cpu {
+        print_cpu_usage(cpu);
+    }
+
+    // Memory Usage
+    if let Some(memory) = &report.memory {
+        print_memory_usage(memory, units);
+    }
+
+    // Disk Usage
+    if let Some(disks) = &report.disks {
+        print_disk_usage(disks, units);
+    }
+
+    // Disk I/O
+    if let Some(disk_io) = &report.disk_io {
+        print_disk_io(disk_io, units);
+    }
+
+    // Block device tree
+    if let Some(devices) = &report.block_devices {
+        print_block_devices(devices, units);
+    }
+
+    // Software RAID
+    if let Some(arrays) = &report.raid {
+        print_raid(arrays, units);
+    }
+
+    // Pressure stall information
+    if let Some(pressure) = &report.pressure {
+        print_pressure(pressure);
+    }
+
+    // Top processes by CPU
+    if let Some(processes) = &report.top_processes_cpu {
+        print_top_processes_cpu(processes);
+    }
+
+    // Top processes by Memory
+    if let Some(processes) = &report.top_processes_memory {
+        print_top_processes_memory(processes, units);
+    }
+
+    // Listening ports
+    if let Some(ports) = &report.ports {
+        print_listening_ports(ports);
+    }
+
+    // Additional system information
+    if report.system.is_some() || report.network.is_some() || report.users.is_some() {
+        print_additional_info(report.system.as_ref(), report.network.as_ref(), report.users.as_ref(), options);
+    }
+
+    // SSH logins
+    if let Some(ssh) = &report.ssh {
+        print_ssh_activity(ssh);
+    }
+
+    // Alerts
+    if let Some(alerts) = &report.alerts {
+        print_alerts(alerts);
+    }
+}
+
+fn print_footer() {
+    println!();
+    println!("=========================================");
+    println!("       END OF REPORT");
+    println!("=========================================");
+}
+
+fn print_header(title: &str) {
+    println!();
+    println!("--- {} ---", title);
+}
+
+fn print_cpu_usage(cpu: &CpuUsage) {
+    print_header("CPU USAGE");
+
+    if let Some(model) = &cpu.model {
+        println!("CPU Model: {}", model);
+    }
+    if let Some(vendor) = &cpu.vendor {
+        println!("CPU Vendor: {}", vendor);
+    }
+    println!("CPU Usage: {:.2}%", cpu.usage_percent);
+    println!("CPU Idle: {:.2}%", cpu.idle_percent);
+    println!("CPU Cores: {}", cpu.cores);
+
+    if let Some(times) = &cpu.times {
+        let modes: Vec<_> = times.modes().iter().map(|(mode, percent)| format!("{} {:.1}%", mode, percent)).collect();
+        println!("CPU Time: {}", modes.join(", "));
+    }
+
+    if !cpu.per_core.is_empty() {
+        println!();
+        println!("{:<7}{:<32}{:<11}{:<11}{:<11}GOVERNOR", "CORE", "USAGE", "FREQ", "MIN", "MAX");
+        for core in &cpu.per_core {
+            println!(
+                "{:<7}{} {:>6.1}%  {:<11}{:<11}{:<11}{}",
+                format!("cpu{}", core.core),
+                usage_bar(core.usage_percent as f64, CORE_BAR_WIDTH),
+                core.usage_percent,
+                format_mhz(core.frequency_mhz),
+                format_mhz(core.min_frequency_mhz),
+                format_mhz(core.max_frequency_mhz),
+                core.governor.as_deref().unwrap_or("-")
+            );
+        }
+    }
+
+    // The overall line above already says it all on a single core.
+    if cpu.per_core.len() > 1 && cpu.per_core.iter().any(|core| core.times.is_some()) {
+        println!();
+        let header: Vec<_> = CpuTimes::MODES.iter().map(|mode| format!("{:>8}", mode)).collect();
+        println!("{:<7}{}", "CORE", header.join(""));
+        for core in &cpu.per_core {
+            let Some(times) = &core.times else {
+                continue;
+            };
+            let percents: Vec<_> = times.modes().iter().map(|(_, percent)| format!("{:>7.1}%", percent)).collect();
+            println!("{:<7}{}", format!("cpu{}", core.core), percents.join(""));
+        }
+    }
+}
+
+/// `[#####...............]` with `width` cells between the brackets.
+fn usage_bar(percent: f64, width: usize) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * width as f64).round() as usize;
+    format!("[{}{}]", "#".repeat(filled), ".".repeat(width - filled))
+}
+
+fn format_mhz(mhz: Option<u64>) -> String {
+    mhz.map_or_else(|| "-".to_string(), |mhz| format!("{} MHz", mhz))
+}
+
+fn print_memory_usage(memory: &MemoryUsage, units: Units) {
+    print_header("MEMORY USAGE");
+
+    println!("Total Memory: {:.2} GB", units.gb(memory.total_bytes));
+    println!("Used Memory: {:.2} GB ({:.2}%)", units.gb(memory.used_bytes), memory.used_percent);
+    println!("Available Memory: {:.2} GB ({:.2}%)", units.gb(memory.available_bytes), memory.available_percent);
+
+    // Swap information
+    if let Some(swap) = &memory.swap {
+        print_swap(swap, units);
+    } else {
+        println!("Swap: Not configured");
+    }
+
+    if let Some(details) = &memory.details {
+        print_memory_details(details, units);
+    }
+}
+
+fn print_swap(swap: &SwapUsage, units: Units) {
+    println!("Total Swap: {:.2} GB", units.gb(swap.total_bytes));
+    println!("Used Swap: {:.2} GB ({:.2}%)", units.gb(swap.used_bytes), swap.used_percent);
+    if let (Some(swap_in), Some(swap_out)) = (swap.swap_in_pages_per_second, swap.swap_out_pages_per_second) {
+        println!("Swap Activity: {:.1} pages/s in, {:.1} pages/s out", swap_in, swap_out);
+    }
+
+    if let Some(zswap) = &swap.zswap {
+        if zswap.enabled {
+            let mut settings = Vec::new();
+            settings.extend(zswap.compressor.clone());
+            settings.extend(zswap.max_pool_percent.map(|percent| format!("max pool {}%", percent)));
+            print!("Zswap: enabled ({})", settings.join(", "));
+            if let (Some(stored), Some(pool)) = (zswap.stored_bytes, zswap.pool_bytes) {
+                print!(", {:.2} GB stored in {:.2} GB", units.gb(stored), units.gb(pool));
+            }
+            println!();
+        } else {
+            println!("Zswap: disabled");
+        }
+    }
+
+    if !swap.devices.is_empty() {
+        println!("  {:<24} {:<10} {:<10} {:<10} PRIORITY", "DEVICE", "TYPE", "SIZE", "USED");
+        for device in &swap.devices {
+            println!("  {:<24} {:<10} {:<10} {:<10} {}",
+                     device.name,
+                     device.kind,
+                     format!("{:.2} GB", units.gb(device.size_bytes)),
+                     format!("{:.2} GB", units.gb(device.used_bytes)),
+                     device.priority);
+            if let Some(zram) = &device.zram {
+                println!("    zram: {:.2} GB compressed to {:.2} GB ({:.2}x{}), {:.2} GB of RAM used",
+                         units.gb(zram.original_bytes),
+                         units.gb(zram.compressed_bytes),
+                         zram.compression_ratio,
+                         zram.algorithm.as_ref().map_or_else(String::new, |algorithm| format!(", {}", algorithm)),
+                         units.gb(zram.memory_used_bytes));
+            }
+        }
+    }
+}
+
+fn print_memory_details(details: &MemoryDetails, units: Units) {
+    println!();
+    println!("Buffers: {:.2} GB", units.gb(details.buffers_bytes));
+    println!("Page Cache: {:.2} GB (shmem/tmpfs {:.2} GB)", units.gb(details.cached_bytes), units.gb(details.shmem_bytes));
+    println!("Slab: {:.2} GB (reclaimable {:.2} GB, unreclaimable {:.2} GB)",
+             units.gb(details.slab_reclaimable_bytes + details.slab_unreclaimable_bytes),
+             units.gb(details.slab_reclaimable_bytes),
+             units.gb(details.slab_unreclaimable_bytes));
+    println!("Anonymous: {:.2} GB (transparent huge pages {:.2} GB)",
+             units.gb(details.anon_bytes),
+             units.gb(details.anon_huge_pages_bytes));
+    println!("Mapped: {:.2} GB", units.gb(details.mapped_bytes));
+    println!("Dirty: {:.2} MB, Writeback: {:.2} MB",
+             units.mb(details.dirty_bytes as f64),
+             units.mb(details.writeback_bytes as f64));
+    println!("Committed: {:.2} GB of {:.2} GB limit ({:.2}%)",
+             units.gb(details.committed_bytes),
+             units.gb(details.commit_limit_bytes),
+             details.committed_bytes as f64 / details.commit_limit_bytes.max(1) as f64 * 100.0);
+    if details.huge_pages_total > 0 {
+        println!("Huge Pages: {} of {} free, {:.0} kB each",
+                 details.huge_pages_free,
+                 details.huge_pages_total,
+                 units.kb(details.huge_page_size_bytes as f64));
+    }
+}
+
+fn print_disk_usage(disks: &[DiskUsage], units: Units) {
+    print_header("DISK USAGE");
+
+    println!("{:<20} {:<8} {:<10} {:<10} {:<10} {:<8} {:<8} {:<4} Mounted on",
+             "Filesystem", "Type", "Size", "Used", "Available", "Use%", "IUse%", "Mode");
+
+    for disk in disks {
+        println!("{:<20} {:<8} {:<10} {:<10} {:<10} {:<7.1}% {:<8} {:<4} {}",
+                 disk.filesystem,
+                 disk.fs_type,
+                 format!("{:.1}G", units.gb(disk.total_bytes)),
+                 format!("{:.1}G", units.gb(disk.used_bytes)),
+                 format!("{:.1}G", units.gb(disk.available_bytes)),
+                 disk.used_percent,
+                 disk.inodes.as_ref().map_or_else(|| "-".to_string(), |inodes| format!("{:.1}%", inodes.used_percent)),
+                 if disk.read_only { "ro" } else { "rw" },
+                 disk.mount_point);
+    }
+
+    let forecasts: Vec<_> = disks.iter().filter_map(|disk| Some((disk, disk.forecast?))).collect();
+    if forecasts.is_empty() {
+        return;
+    }
+    let window = forecasts.iter().map(|(_, forecast)| forecast.window_seconds).max().unwrap_or(0);
+    println!();
+    println!("Fill rate (over the last {}):", humantime::format_duration(Duration::from_secs(window)));
+    for (disk, forecast) in forecasts {
+        let per_day = forecast.growth_bytes_per_second * 86400.0;
+        let sign = if per_day < 0.0 { "-" } else { "+" };
+        let outlook = match forecast.seconds_until_full {
+            Some(seconds) => format!("full in {}", format_time_left(seconds)),
+            None => "not growing".to_string(),
+        };
+        println!("  {:<20} {}{:.2}G/day, {}", disk.mount_point, sign, units.gb(per_day.abs() as u64), outlook);
+    }
+}
+
+/// Rounded the way a person would say it: `~40 minutes`, `~5 hours`, `~3 days`.
+fn format_time_left(seconds: u64) -> String {
+    match seconds {
+        0..7200 => format!("~{} minutes", seconds.div_ceil(60)),
+        7200..172800 => format!("~{} hours", seconds / 3600),
+        _ => format!("~{} days", seconds / 86400),
+    }
+}
+
+fn print_disk_io(devices: &[DiskIo], units: Units) {
+    print_header("DISK I/O");
+
+    if devices.is_empty() {
+        println!("No block device statistics available");
+        return;
+    }
+
+    println!("{:<12} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>7} {:>6}  Mounted on",
+             "Device", "r/s", "w/s", "rMB/s", "wMB/s", "r_await", "w_await", "aqu-sz", "%util");
+
+    for device in devices {
+        let mount_points = if device.mount_points.is_empty() { "-".to_string() } else { device.mount_points.join(", ") };
+        let Some(rates) = &device.rates else {
+            println!("{:<12} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>7} {:>6}  {}",
+                     device.device, "-", "-", "-", "-", "-", "-", "-", "-", mount_points);
+            continue;
+        };
+        println!("{:<12} {:>8.1} {:>8.1} {:>8.2} {:>8.2} {:>8.2} {:>8.2} {:>7.2} {:>5.1}%  {}",
+                 device.device,
+                 rates.reads_per_second,
+                 rates.writes_per_second,
+                 units.mb(rates.read_bytes_per_second),
+                 units.mb(rates.write_bytes_per_second),
+                 rates.read_await_ms,
+                 rates.write_await_ms,
+                 rates.queue_depth,
+                 rates.utilization_percent,
+                 mount_points);
+    }
+}
+
+fn print_block_devices(devices: &[BlockDevice], units: Units) {
+    print_header("BLOCK DEVICES");
+
+    if devices.is_empty() {
+        println!("No block devices found");
+        return;
+    }
+
+    println!("{:<24} {:<6} {:>9} {:<4} {:<12} {:<4} Mounted on", "Name", "Type", "Size", "Rota", "Scheduler", "Mode");
+
+    for device in devices {
+        print_block_device(device, "", "", units);
+    }
+}
+
+/// One line per device, children indented below it with tree branches.
+fn print_block_device(device: &BlockDevice, branch: &str, indent: &str, units: Units) {
+    let name = match device.mapper_name.as_ref().or(device.backing_file.as_ref()) {
+        Some(alias) => format!("{}{} ({})", branch, device.name, alias),
+        None => format!("{}{}", branch, device.name),
+    };
+    println!("{:<24} {:<6} {:>9} {:<4} {:<12} {:<4} {}",
+             name,
+             device.kind,
+             format!("{:.1}G", units.gb(device.size_bytes)),
+             if device.rotational { "hdd" } else { "ssd" },
+             device.scheduler.as_deref().unwrap_or("-"),
+             if device.read_only { "ro" } else { "rw" },
+             device.mount_points.join(", "));
+
+    for (index, child) in device.children.iter().enumerate() {
+        let last = index + 1 == device.children.len();
+        let branch = format!("{}{}", indent, if last { "└─" } else { "├─" });
+        let indent = format!("{}{}", indent, if last { "  " } else { "│ " });
+        print_block_device(child, &branch, &indent, units);
+    }
+}
+
+fn print_raid(arrays: &[RaidArray], units: Units) {
+    print_header("SOFTWARE RAID");
+
+    if arrays.is_empty() {
+        println!("No md arrays found");
+        return;
+    }
+
+    for array in arrays {
+        let devices = match (array.raid_devices, array.working_devices) {
+            (Some(raid_devices), Some(working)) => format!("[{}/{}]", raid_devices, working),
+            _ => "-".to_string(),
+        };
+        println!("{:<8} {:<8} {:<12} {:<8} {}",
+                 array.name,
+                 array.level.as_deref().unwrap_or("-"),
+                 array.state,
+                 devices,
+                 if array.is_degraded() { "DEGRADED" } else { "OK" });
+
+        let members: Vec<_> = array
+            .members
+            .iter()
+            .map(|member| match member.state {
+                RaidMemberState::Active => member.device.clone(),
+                state => format!("{} ({})", member.device, state.label()),
+            })
+            .collect();
+        println!("  Members: {}", members.join(", "));
+
+        if let Some(sync) = &array.sync {
+            let Some(progress) = sync.progress_percent else {
+                println!("  {}: pending", sync.action);
+                continue;
+            };
+            let mut line = format!("  {}: {:.1}%", sync.action, progress);
+            if let Some(speed) = sync.speed_bytes_per_second {
+                line.push_str(&format!(" at {:.1} MB/s", units.mb(speed as f64)));
+            }
+            if let Some(finish) = sync.finish_seconds {
+                line.push_str(&format!(", {} left", humantime::format_duration(Duration::from_secs(finish))));
+            }
+            println!("{}", line);
+        }
+    }
+}
+
+fn print_top_processes_cpu(processes: &[ProcessInfo]) {
+    print_header(&format!("TOP {} PROCESSES BY CPU USAGE", processes.len()));
+
+    println!("{:<8} {:<12} {:<8} COMMAND", "PID", "USER", "CPU%");
+
+    for process in processes {
+        println!("{:<8} {:<12} {:<7.2} {}",
+                 process.pid,
+                 process.user,
+                 process.cpu_percent,
+                 process.name);
+    }
+}
+
+fn print_top_processes_memory(processes: &[ProcessInfo], units: Units) {
+    print_header(&format!("TOP {} PROCESSES BY MEMORY USAGE", processes.len()));
+
+    println!("{:<8} {:<12} {:<8} {:<10} COMMAND", "PID", "USER", "MEM%", "MEMORY");
+
+    for process in processes {
+        println!("{:<8} {:<12} {:<7.2} {:<10} {}",
+                 process.pid,
+                 process.user,
+                 process.memory_percent,
+                 format!("{:.1}M", units.mb(process.memory_bytes as f64)),
+                 process.name);
+    }
+}
+
+fn print_listening_ports(ports: &[ListeningPort]) {
+    print_header("LISTENING PORTS");
+
+    if ports.is_empty() {
+        println!("No listening sockets found");
+        return;
+    }
+
+    println!("{:<6} {:<40} {:<8} {:<12} COMMAND", "PROTO", "ADDRESS", "PID", "USER");
+
+    for port in ports {
+        let pid = port.pid.map_or("-".to_string(), |pid| pid.to_string());
+        println!("{:<6} {:<40} {:<8} {:<12} {}",
+                 port.protocol.label(),
+                 SocketAddr::new(port.address, port.port).to_string(),
+                 pid,
+                 port.user.as_deref().unwrap_or("-"),
+                 port.process.as_deref().unwrap_or("-"));
+    }
+}
+
+fn print_additional_info(
+    system: Option<&SystemInfo>,
+    network: Option<&NetworkInfo>,
+    users: Option<&UsersInfo>,
+    options: &TextOptions,
+) {
+    print_header("ADDITIONAL SYSTEM INFORMATION");
+
+    if let Some(system) = system {
+        print_system_info(system);
+    }
+
+    // Network interfaces
+    if let Some(network) = network {
+        print_network_info(network, options.units);
+    }
+
+    // Logged in users
+    if let Some(users) = users {
+        print_logged_users(users, options.logged_users);
+    }
+
+    // Boot time
+    if let Some(system) = system {
+        println!("Boot time: {}",
+                 system.boot_time
+                     .unwrap_or_default()
+                     .format("%Y-%m-%d %H:%M:%S"));
+    }
+}
+
+fn print_system_info(system: &SystemInfo) {
+    // OS Information
+    println!("OS: {} {}", system.os_name.as_deref().unwrap_or("Unknown"),
+             system.os_version.as_deref().unwrap_or("Unknown"));
+    println!("Kernel: {}", system.kernel_version.as_deref().unwrap_or("Unknown"));
+
+    // System uptime
+    let uptime_seconds = system.uptime_seconds;
+    let days = uptime_seconds / 86400;
+    let hours = (uptime_seconds % 86400) / 3600;
+    let minutes = (uptime_seconds % 3600) / 60;
+    println!("Uptime: {} days, {} hours, {} minutes", days, hours, minutes);
+
+    // Load average
+    let load_avg = &system.load_average;
+    println!("Load Average: {:.2}, {:.2}, {:.2}", load_avg.one, load_avg.five, load_avg.fifteen);
+
+    // Load per core
+    println!("Load per core: {:.2}", system.load_per_core);
+}
+
+fn print_network_info(network: &NetworkInfo, units: Units) {
+    println!();
+    println!("Network Interfaces:");
+
+    for interface in &network.interfaces {
+        println!("  {}: RX: {:.2} MB, TX: {:.2} MB",
+                 interface.name,
+                 units.mb(interface.received_bytes as f64),
+                 units.mb(interface.transmitted_bytes as f64));
+    }
+
+    // Count listening ports
+    if let Some(listening_ports) = network.listening_ports {
+        println!("Listening ports: {}", listening_ports);
+    }
+
+    if let Some(sockets) = &network.sockets {
+        print_tcp_states("IPv4", &sockets.ipv4);
+        print_tcp_states("IPv6", &sockets.ipv6);
+        println!("UDP sockets: {} IPv4, {} IPv6", sockets.ipv4.udp, sockets.ipv6.udp);
+    }
+}
+
+fn print_tcp_states(family: &str, counts: &SocketCounts) {
+    let states: Vec<_> = counts.tcp.iter().map(|(state, count)| format!("{} {}", state, count)).collect();
+    if states.is_empty() {
+        println!("TCP sockets ({}): none", family);
+    } else {
+        println!("TCP sockets ({}): {}", family, states.join(", "));
+    }
+}
+
+fn print_logged_users(users: &UsersInfo, limit: usize) {
+    println!();
+    println!("Currently Logged in Users:");
+
+    if let Some(logged_in) = &users.logged_in {
+        if !logged_in.is_empty() {
+            println!("  {:<12} {:<10} {:<20} {:<17} {:<6} PID", "USER", "TTY", "FROM", "LOGIN@", "IDLE");
+        }
+        for session in logged_in.iter().take(limit) {
+            println!("  {:<12} {:<10} {:<20} {:<17} {:<6} {}",
+                     session.user,
+                     session.tty,
+                     session.host.as_deref().unwrap_or("-"),
+                     format_time(session.login_time),
+                     session.idle_seconds.map_or("?".to_string(), format_idle),
+                     session.pid);
+        }
+
+        println!("Total logged in users: {}", logged_in.len());
+    } else {
+        println!("  Unable to retrieve user information");
+    }
+
+    // Login history
+    println!();
+    println!("Recent Logins:");
+    match &users.recent_logins {
+        Some(logins) if !logins.is_empty() => {
+            println!("  {:<12} {:<10} {:<20} {:<17} LOGOUT", "USER", "TTY", "FROM", "LOGIN@");
+            for login in logins {
+                println!("  {:<12} {:<10} {:<20} {:<17} {}",
+                         login.user,
+                         login.tty,
+                         login.host.as_deref().unwrap_or("-"),
+                         format_time(login.login_time),
+                         login.logout_time.map_or("still logged in".to_string(), format_time));
+            }
+        }
+        Some(_) => println!("  No logins recorded"),
+        None => println!("  Unable to read login history"),
+    }
+
+    // Failed login attempts
+    println!();
+    match &users.failed_logins {
+        Some(failed_logins) => print_failed_logins(failed_logins),
+        None => {
+            println!("Recent Failed Login Attempts:");
+            match &users.failed_logins_error {
+                Some(err) => println!("  Unable to retrieve failed login information: {}", err),
+                None => println!("  Unable to retrieve failed login information"),
+            }
+        }
+    }
+}
+
+fn print_failed_logins(failed_logins: &FailedLogins) {
+    let window = humantime::format_duration(Duration::from_secs(failed_logins.window_seconds));
+    println!("Recent Failed Login Attempts (last {}): {}", window, failed_logins.attempts);
+    if failed_logins.attempts == 0 {
+        println!("  No failed login attempts found");
+        return;
+    }
+
+    println!("  {:<24} {:<9} {:<6} {:<17} LAST SEEN", "SOURCE", "ATTEMPTS", "USERS", "FIRST SEEN");
+    for source in failed_logins.by_source.iter().take(FAILED_LOGIN_ROWS) {
+        println!("  {:<24} {:<9} {:<6} {:<17} {}{}",
+                 source.source,
+                 source.attempts,
+                 source.users,
+                 format_time(source.first_seen),
+                 format_time(source.last_seen),
+                 if source.brute_force { "  BRUTE FORCE" } else { "" });
+    }
+
+    println!();
+    println!("  {:<24} {:<9} {:<6} {:<17} LAST SEEN", "USER", "ATTEMPTS", "FROM", "FIRST SEEN");
+    for user in failed_logins.by_user.iter().take(FAILED_LOGIN_ROWS) {
+        println!("  {:<24} {:<9} {:<6} {:<17} {}",
+                 user.user,
+                 user.attempts,
+                 user.sources,
+                 format_time(user.first_seen),
+                 format_time(user.last_seen));
+    }
+}
+
+fn print_ssh_activity(ssh: &SshActivity) {
+    let window = humantime::format_duration(Duration::from_secs(ssh.window_seconds));
+    print_header(&format!("SSH ACTIVITY (LAST {})", window.to_string().to_uppercase()));
+
+    for err in &ssh.errors {
+        println!("Unable to read authentication log: {}", err);
+    }
+    if ssh.files.is_empty() {
+        if ssh.errors.is_empty() {
+            println!("No authentication log found");
+        }
+        return;
+    }
+
+    println!("Accepted: {}, Failed: {}, Invalid users: {}, Disconnects: {}",
+             ssh.accepted, ssh.failed, ssh.invalid_users, ssh.disconnects);
+    print_ssh_summaries("USER", &ssh.by_user);
+    print_ssh_summaries("SOURCE", &ssh.by_source);
+}
+
+fn print_ssh_summaries(title: &str, summaries: &[SshSummary]) {
+    if summaries.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("  {:<24} {:<9} {:<7} {:<8} LAST SEEN", title, "ACCEPTED", "FAILED", "INVALID");
+    for summary in summaries.iter().take(FAILED_LOGIN_ROWS) {
+        println!("  {:<24} {:<9} {:<7} {:<8} {}",
+                 summary.name,
+                 summary.accepted,
+                 summary.failed,
+                 summary.invalid_user,
+                 format_time(summary.last_seen));
+    }
+}
+
+fn print_pressure(pressure: &PressureInfo) {
+    print_header("PRESSURE STALL INFORMATION");
+
+    if pressure.system.is_none() && pressure.cgroups.is_empty() {
+        println!("Not available: the kernel has no PSI support or was booted with psi=0");
+        return;
+    }
+
+    println!("{:<24} {:<9} {:<22} {:<22} STALLED", "SCOPE", "RESOURCE", "SOME 10s/60s/300s", "FULL 10s/60s/300s");
+    for (scope, resources) in pressure.scopes() {
+        for (resource, stats) in resources.resources() {
+            print_pressure_row(scope, resource, stats);
+        }
+    }
+}
+
+fn print_pressure_row(scope: &str, resource: &str, stats: &PressureStats) {
+    let averages = |stall: &StallTime| format!("{:.2} / {:.2} / {:.2}%", stall.avg10, stall.avg60, stall.avg300);
+    // Total `some` stall time, rounded to whole seconds.
+    let stalled = Duration::from_secs(stats.some.total_microseconds / 1_000_000);
+    println!("{:<24} {:<9} {:<22} {:<22} {}",
+             scope,
+             resource,
+             averages(&stats.some),
+             stats.full.as_ref().map_or_else(|| "-".to_string(), averages),
+             humantime::format_duration(stalled));
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+/// Idle time the way `who -u` shows it: `.` for active, `old` after a day.
+fn format_idle(seconds: u64) -> String {
+    match seconds {
+        0..60 => ".".to_string(),
+        60..86400 => format!("{:02}:{:02}", seconds / 3600, seconds % 3600 / 60),
+        _ => "old".to_string(),
+    }
+}
+
+fn print_alerts(alerts: &[Alert]) {
+    print_header("ALERTS");
+
+    if alerts.is_empty() {
+        println!("No alerts firing");
+        return;
+    }
+
+    for alert in alerts {
+        let metric = match &alert.instance {
+            Some(instance) => format!("{}{{{}}}", alert.metric.name(), instance),
+            None => alert.metric.name().to_string(),
+        };
+        println!("[{}] {}: {} = {:.2} {} {} ({} since {})",
+                 alert.severity.label(),
+                 alert.rule,
+                 metric,
+                 alert.value,
+                 alert.op.symbol(),
+                 alert.threshold,
+                 alert.state.label(),
+                 alert.since.format("%Y-%m-%d %H:%M:%S"));
+    }
+}
+
+fn print_delta(delta: Option<&ReportDelta>, units: Units) {
+    let Some(delta) = delta else {
+        print_header("CHANGE SINCE PREVIOUS SAMPLE");
+        println!("Waiting for the next sample...");
+        return;
+    };
+
+    print_header(&format!("CHANGE SINCE PREVIOUS SAMPLE ({:.1}s)", delta.elapsed_seconds));
+
+    if let Some(cpu_usage) = delta.cpu_usage_percent {
+        println!("CPU Usage: {:+.2} pts", cpu_usage);
+    }
+    if let Some(memory_used) = delta.memory_used_bytes {
+        println!("Used Memory: {:+.2} MB", units.mb(memory_used as f64));
+    }
+
+    for disk in &delta.disks {
+        println!("Disk {}: {:+.2} MB", disk.mount_point, units.mb(disk.used_bytes as f64));
+    }
+
+    let elapsed = delta.elapsed_seconds.max(f64::EPSILON);
+    for interface in &delta.interfaces {
+        let received_mb = units.mb(interface.received_bytes as f64);
+        let transmitted_mb = units.mb(interface.transmitted_bytes as f64);
+        println!("Network {}: RX +{:.2} MB ({:.2} MB/s), TX +{:.2} MB ({:.2} MB/s)",
+                 interface.name,
+                 received_mb,
+                 received_mb / elapsed,
+                 transmitted_mb,
+                 transmitted_mb / elapsed);
+    }
+}
@@ -0,0 +1,954 @@
+//! Typed results of the collectors. Structs and enums are `#[non_exhaustive]`
+//! so fields and sections can be added in minor releases.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::alert::Alert;
+
+/// The parts of the report that can be selected with `--only` / `--skip`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+#[serde(rename_all = "kebab-case")]
+pub enum Section {
+    Cpu,
+    Memory,
+    Disk,
+    TopCpu,
+    TopMemory,
+    System,
+    Network,
+    Ports,
+    Users,
+    Ssh,
+    Pressure,
+    BlockDevices,
+    Raid,
+}
+
+impl Section {
+    pub const ALL: [Section; 13] = [
+        Section::Cpu,
+        Section::Memory,
+        Section::Disk,
+        Section::TopCpu,
+        Section::TopMemory,
+        Section::System,
+        Section::Network,
+        Section::Ports,
+        Section::Users,
+        Section::Ssh,
+        Section::Pressure,
+        Section::BlockDevices,
+        Section::Raid,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Section::Cpu => "cpu",
+            Section::Memory => "memory",
+            Section::Disk => "disk",
+            Section::TopCpu => "top-cpu",
+            Section::TopMemory => "top-memory",
+            Section::System => "system",
+            Section::Network => "network",
+            Section::Ports => "ports",
+            Section::Users => "users",
+            Section::Ssh => "ssh",
+            Section::Pressure => "pressure",
+            Section::BlockDevices => "block-devices",
+            Section::Raid => "raid",
+        }
+    }
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Section {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Section::ALL
+            .into_iter()
+            .find(|section| section.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Section::ALL.iter().map(|section| section.name()).collect();
+                format!("unknown section '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// A single snapshot of everything the report prints. Sections that were
+/// not selected are `None` and left out of the structured outputs.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Report {
+    pub generated_at: DateTime<Local>,
+    pub hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<CpuUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disks: Option<Vec<DiskUsage>>,
+    /// Block device activity, collected with the disk section.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_io: Option<Vec<DiskIo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_processes_cpu: Option<Vec<ProcessInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_processes_memory: Option<Vec<ProcessInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<SystemInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkInfo>,
+    /// Listening sockets; empty when `/proc/net` could not be read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports: Option<Vec<ListeningPort>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub users: Option<UsersInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssh: Option<SshActivity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<PressureInfo>,
+    /// Physical disks at the top, with what is built on them below.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_devices: Option<Vec<BlockDevice>>,
+    /// Linux software RAID arrays; empty when md is not in use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raid: Option<Vec<RaidArray>>,
+    /// Pending and firing alerts, `None` when no rules are configured.
+    pub alerts: Option<Vec<Alert>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct CpuUsage {
+    pub usage_percent: f32,
+    pub idle_percent: f32,
+    pub cores: usize,
+    /// Model name from `/proc/cpuinfo`, e.g. `Intel(R) Xeon(R) Gold 6230 CPU @ 2.10GHz`.
+    pub model: Option<String>,
+    /// Vendor id from `/proc/cpuinfo`, e.g. `GenuineIntel` or `AuthenticAMD`.
+    pub vendor: Option<String>,
+    /// Time spent in each mode across all cores, `None` when `/proc/stat` is
+    /// unavailable or only one sample has been taken.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub times: Option<CpuTimes>,
+    pub per_core: Vec<CoreUsage>,
+}
+
+/// Percentage of CPU time spent in each mode between two samples of
+/// `/proc/stat`. Guest time is counted in `user` and `nice`.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[non_exhaustive]
+pub struct CpuTimes {
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub idle: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub softirq: f64,
+    pub steal: f64,
+}
+
+impl CpuTimes {
+    /// Mode names in `/proc/stat` order.
+    pub const MODES: [&'static str; 8] = ["user", "nice", "system", "idle", "iowait", "irq", "softirq", "steal"];
+
+    /// Mode names and percentages in `/proc/stat` order.
+    pub fn modes(&self) -> [(&'static str, f64); 8] {
+        let percents = [self.user, self.nice, self.system, self.idle, self.iowait, self.irq, self.softirq, self.steal];
+        std::array::from_fn(|i| (Self::MODES[i], percents[i]))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct CoreUsage {
+    /// Logical core number, as in `cpuN` of `/proc/stat`.
+    pub core: usize,
+    pub usage_percent: f32,
+    /// Current frequency, `None` when the kernel does not report it.
+    pub frequency_mhz: Option<u64>,
+    /// Hardware limits from cpufreq, `None` without a cpufreq driver (common
+    /// in VMs).
+    pub min_frequency_mhz: Option<u64>,
+    pub max_frequency_mhz: Option<u64>,
+    /// cpufreq scaling governor, e.g. `performance` or `powersave`.
+    pub governor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub times: Option<CpuTimes>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct MemoryUsage {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub used_percent: f64,
+    pub available_percent: f64,
+    /// `None` when no swap is configured.
+    pub swap: Option<SwapUsage>,
+    /// Breakdown from `/proc/meminfo`, `None` when it cannot be read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<MemoryDetails>,
+}
+
+/// Where the memory goes, from `/proc/meminfo`. Fields the running kernel
+/// does not report are 0.
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct MemoryDetails {
+    /// Block device buffers.
+    pub buffers_bytes: u64,
+    /// Page cache, including shmem.
+    pub cached_bytes: u64,
+    /// Shared memory and tmpfs, which cannot be dropped like other cache.
+    pub shmem_bytes: u64,
+    pub slab_reclaimable_bytes: u64,
+    pub slab_unreclaimable_bytes: u64,
+    /// Anonymous memory of processes: heap, stacks, private mappings.
+    pub anon_bytes: u64,
+    /// Files mapped into processes, e.g. shared libraries.
+    pub mapped_bytes: u64,
+    /// Waiting to be written back to disk.
+    pub dirty_bytes: u64,
+    /// Being written back right now.
+    pub writeback_bytes: u64,
+    /// Memory the kernel promised to processes (`Committed_AS`).
+    pub committed_bytes: u64,
+    /// What can be committed under strict overcommit (`vm.overcommit_memory = 2`).
+    pub commit_limit_bytes: u64,
+    pub huge_pages_total: u64,
+    pub huge_pages_free: u64,
+    pub huge_page_size_bytes: u64,
+    /// Transparent huge pages backing anonymous memory.
+    pub anon_huge_pages_bytes: u64,
+}
+
+impl MemoryDetails {
+    /// Every byte size with a short name, for exporting.
+    pub fn bytes(&self) -> [(&'static str, u64); 12] {
+        [
+            ("buffers", self.buffers_bytes),
+            ("cached", self.cached_bytes),
+            ("shmem", self.shmem_bytes),
+            ("slab_reclaimable", self.slab_reclaimable_bytes),
+            ("slab_unreclaimable", self.slab_unreclaimable_bytes),
+            ("anon", self.anon_bytes),
+            ("mapped", self.mapped_bytes),
+            ("dirty", self.dirty_bytes),
+            ("writeback", self.writeback_bytes),
+            ("committed", self.committed_bytes),
+            ("commit_limit", self.commit_limit_bytes),
+            ("anon_huge_pages", self.anon_huge_pages_bytes),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SwapUsage {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub used_percent: f64,
+    /// Pages swapped in and out since boot, from `/proc/vmstat`.
+    pub swapped_in_pages: Option<u64>,
+    pub swapped_out_pages: Option<u64>,
+    /// Swap activity over the sample window; `None` until two samples have
+    /// been taken. Sustained swap-in together with swap-out means thrashing.
+    pub swap_in_pages_per_second: Option<f64>,
+    pub swap_out_pages_per_second: Option<f64>,
+    /// Active swap areas from `/proc/swaps`.
+    pub devices: Vec<SwapDevice>,
+    /// `None` when the kernel has no zswap.
+    pub zswap: Option<ZswapStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SwapDevice {
+    /// Device or file path.
+    pub name: String,
+    /// `partition` or `file`.
+    pub kind: String,
+    pub size_bytes: u64,
+    pub used_bytes: u64,
+    /// Higher priorities are used first; equal ones are striped.
+    pub priority: i32,
+    /// Compression statistics when the device is a zram disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zram: Option<ZramStats>,
+}
+
+/// Compressed RAM block device, from `/sys/block/zramN/mm_stat`.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct ZramStats {
+    pub disk_size_bytes: u64,
+    /// Data stored, before compression.
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+    /// RAM taken by the device, including allocator overhead.
+    pub memory_used_bytes: u64,
+    /// `original_bytes / compressed_bytes`, 0 when empty.
+    pub compression_ratio: f64,
+    pub algorithm: Option<String>,
+}
+
+/// Compressed cache in front of swap.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct ZswapStats {
+    pub enabled: bool,
+    pub compressor: Option<String>,
+    /// Largest share of RAM the pool may take.
+    pub max_pool_percent: Option<u64>,
+    /// RAM taken by the compressed pool, `None` before Linux 5.19.
+    pub pool_bytes: Option<u64>,
+    /// Data stored in the pool, before compression.
+    pub stored_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct DiskUsage {
+    /// Mount source, usually the device.
+    pub filesystem: String,
+    pub mount_point: String,
+    /// e.g. `ext4`, `xfs` or `btrfs`.
+    pub fs_type: String,
+    pub read_only: bool,
+    /// Per-mount options such as `rw`, `noatime` or `nosuid`.
+    pub mount_options: Vec<String>,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub used_percent: f64,
+    /// `None` for filesystems without a fixed number of inodes, e.g. btrfs.
+    pub inodes: Option<InodeUsage>,
+    /// `None` until enough samples have been taken, i.e. always for a single
+    /// report.
+    pub forecast: Option<DiskForecast>,
+}
+
+/// Growth of a filesystem fitted by linear regression to the used space of
+/// the samples within the forecast window.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[non_exhaustive]
+pub struct DiskForecast {
+    /// Negative while the filesystem is being cleaned up.
+    pub growth_bytes_per_second: f64,
+    /// When the available space runs out at this rate; `None` unless growing.
+    pub seconds_until_full: Option<u64>,
+    pub samples: usize,
+    /// Time between the oldest and the newest sample.
+    pub window_seconds: u64,
+}
+
+/// Activity of one block device from `/proc/diskstats`.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct DiskIo {
+    /// Kernel name, e.g. `sda`, `nvme0n1p2` or `dm-0`.
+    pub device: String,
+    /// Mount points of the disk section on this device.
+    pub mount_points: Vec<String>,
+    pub reads_completed: u64,
+    pub writes_completed: u64,
+    pub read_bytes: u64,
+    pub written_bytes: u64,
+    pub read_time_ms: u64,
+    pub write_time_ms: u64,
+    /// Time the device had I/O in flight.
+    pub io_time_ms: u64,
+    /// I/O time multiplied by the number of requests in flight.
+    pub weighted_io_time_ms: u64,
+    pub in_flight: u64,
+    /// `None` until two samples have been taken.
+    pub rates: Option<DiskIoRates>,
+}
+
+/// Averages over the sample window, as `iostat -x` shows them.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[non_exhaustive]
+pub struct DiskIoRates {
+    pub reads_per_second: f64,
+    pub writes_per_second: f64,
+    pub read_bytes_per_second: f64,
+    pub write_bytes_per_second: f64,
+    /// Average time a read took, queueing included (`r_await`).
+    pub read_await_ms: f64,
+    pub write_await_ms: f64,
+    /// Average number of requests in flight (`aqu-sz`).
+    pub queue_depth: f64,
+    /// Share of the window the device was busy. Close to 100% means
+    /// saturated for disks that serve one request at a time; SSDs and
+    /// arrays can go further before they are.
+    pub utilization_percent: f64,
+}
+
+/// One node of the block device tree from `/sys/block`, like a line of `lsblk`.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct BlockDevice {
+    /// Kernel name, e.g. `sda`, `sda1`, `dm-0`, `md0` or `loop3`.
+    pub name: String,
+    /// `disk`, `part`, `loop`, `lvm`, `crypt`, `dm` or the RAID level of an
+    /// md array such as `raid1`.
+    pub kind: String,
+    pub size_bytes: u64,
+    /// Spinning disk as far as the kernel knows; virtual disks often claim to be.
+    pub rotational: bool,
+    /// Active I/O scheduler, e.g. `mq-deadline` or `none`.
+    pub scheduler: Option<String>,
+    pub read_only: bool,
+    pub removable: bool,
+    /// Disk model reported by the device.
+    pub model: Option<String>,
+    /// Device mapper name, e.g. `vg0-root`.
+    pub mapper_name: Option<String>,
+    /// File behind a loop device.
+    pub backing_file: Option<String>,
+    /// Mount points of the disk section on this device.
+    pub mount_points: Vec<String>,
+    /// Partitions, then the devices built on this one (device mapper, md).
+    /// A volume spanning several devices appears under each of them.
+    pub children: Vec<BlockDevice>,
+}
+
+/// One md array from `/proc/mdstat` and `/sys/block/mdN/md`.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct RaidArray {
+    /// Kernel name, e.g. `md0`.
+    pub name: String,
+    /// `raid1`, `raid5`, ...; `None` for inactive arrays that were not
+    /// assembled far enough to have one.
+    pub level: Option<String>,
+    /// `array_state` from sysfs (`clean`, `active`, `inactive`, `read-auto`,
+    /// ...), or `active` / `inactive` from `/proc/mdstat`.
+    pub state: String,
+    /// Number of devices the array is built for.
+    pub raid_devices: Option<u64>,
+    /// Members of the array that are in sync.
+    pub working_devices: Option<u64>,
+    /// Missing or failed devices the array is running without.
+    pub degraded_devices: u64,
+    pub members: Vec<RaidMember>,
+    /// Resync, recovery, reshape or check in progress.
+    pub sync: Option<RaidSync>,
+}
+
+impl RaidArray {
+    /// Running without redundancy it was built with, or not running at all.
+    pub fn is_degraded(&self) -> bool {
+        self.degraded_devices > 0 || self.state == "inactive"
+    }
+
+    pub fn failed_members(&self) -> impl Iterator<Item = &RaidMember> {
+        self.members.iter().filter(|member| member.state == RaidMemberState::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct RaidMember {
+    /// Kernel name of the member, e.g. `sda1`.
+    pub device: String,
+    /// Role number in the array.
+    pub slot: Option<u64>,
+    pub state: RaidMemberState,
+}
+
+/// The flag after a member in `/proc/mdstat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum RaidMemberState {
+    Active,
+    /// `(F)`: failed, kept in the array until it is removed.
+    Failed,
+    /// `(S)`: hot spare.
+    Spare,
+    /// `(W)`: write-mostly, only read when nothing else can be.
+    WriteMostly,
+    /// `(R)`: replacement being rebuilt for another member.
+    Replacement,
+    /// `(J)`: write journal.
+    Journal,
+}
+
+impl RaidMemberState {
+    pub fn label(self) -> &'static str {
+        match self {
+            RaidMemberState::Active => "active",
+            RaidMemberState::Failed => "failed",
+            RaidMemberState::Spare => "spare",
+            RaidMemberState::WriteMostly => "write-mostly",
+            RaidMemberState::Replacement => "replacement",
+            RaidMemberState::Journal => "journal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct RaidSync {
+    /// `resync`, `recovery`, `reshape`, `check` or `repair`.
+    pub action: String,
+    /// `None` while the operation is `DELAYED` or `PENDING`.
+    pub progress_percent: Option<f64>,
+    pub speed_bytes_per_second: Option<u64>,
+    /// Kernel estimate of the time left.
+    pub finish_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct InodeUsage {
+    pub total: u64,
+    pub used: u64,
+    pub used_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub user: String,
+    pub name: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub memory_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SystemInfo {
+    pub os_name: Option<String>,
+    pub os_version: Option<String>,
+    pub kernel_version: Option<String>,
+    pub uptime_seconds: u64,
+    pub boot_time: Option<DateTime<Utc>>,
+    pub load_average: LoadAverage,
+    pub load_per_core: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct NetworkInfo {
+    pub interfaces: Vec<InterfaceStats>,
+    /// Listening TCP sockets plus bound, unconnected UDP sockets. `None` when
+    /// `/proc/net` could not be read.
+    pub listening_ports: Option<usize>,
+    /// `None` when `/proc/net` could not be read.
+    pub sockets: Option<SocketStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct InterfaceStats {
+    pub name: String,
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct UsersInfo {
+    /// Sessions from utmp, `None` when it could not be read.
+    pub logged_in: Option<Vec<Session>>,
+    /// Most recent logins from wtmp, newest first; `None` when it could not
+    /// be read.
+    pub recent_logins: Option<Vec<LoginRecord>>,
+    /// Failed logins from btmp, `None` when it could not be read.
+    pub failed_logins: Option<FailedLogins>,
+    /// Why btmp could not be read, usually missing permissions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_logins_error: Option<String>,
+}
+
+/// Open sockets from `/proc/net/{tcp,udp}` and their IPv6 counterparts.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SocketStats {
+    pub ipv4: SocketCounts,
+    pub ipv6: SocketCounts,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[non_exhaustive]
+pub struct SocketCounts {
+    /// TCP sockets per state; states without sockets are left out.
+    pub tcp: BTreeMap<TcpState, usize>,
+    pub udp: usize,
+}
+
+/// A listening TCP socket or a bound, unconnected UDP socket.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct ListeningPort {
+    pub protocol: SocketProtocol,
+    /// Bind address; `0.0.0.0` and `::` accept connections on every interface.
+    pub address: IpAddr,
+    pub port: u16,
+    /// Owning process, `None` when it could not be found (other users'
+    /// processes need root).
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[non_exhaustive]
+#[serde(rename_all = "lowercase")]
+pub enum SocketProtocol {
+    Tcp,
+    Udp,
+}
+
+impl SocketProtocol {
+    pub fn label(self) -> &'static str {
+        match self {
+            SocketProtocol::Tcp => "tcp",
+            SocketProtocol::Udp => "udp",
+        }
+    }
+}
+
+/// Kernel TCP states; the discriminant is the value in `/proc/net/tcp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[non_exhaustive]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TcpState {
+    Established = 1,
+    SynSent = 2,
+    SynRecv = 3,
+    FinWait1 = 4,
+    FinWait2 = 5,
+    TimeWait = 6,
+    Close = 7,
+    CloseWait = 8,
+    LastAck = 9,
+    Listen = 10,
+    Closing = 11,
+    NewSynRecv = 12,
+}
+
+impl TcpState {
+    pub fn from_code(code: u8) -> Option<Self> {
+        let state = match code {
+            1 => TcpState::Established,
+            2 => TcpState::SynSent,
+            3 => TcpState::SynRecv,
+            4 => TcpState::FinWait1,
+            5 => TcpState::FinWait2,
+            6 => TcpState::TimeWait,
+            7 => TcpState::Close,
+            8 => TcpState::CloseWait,
+            9 => TcpState::LastAck,
+            10 => TcpState::Listen,
+            11 => TcpState::Closing,
+            12 => TcpState::NewSynRecv,
+            _ => return None,
+        };
+        Some(state)
+    }
+
+    /// The name `ss` and `netstat` print, e.g. `TIME_WAIT`.
+    pub fn label(self) -> &'static str {
+        match self {
+            TcpState::Established => "ESTABLISHED",
+            TcpState::SynSent => "SYN_SENT",
+            TcpState::SynRecv => "SYN_RECV",
+            TcpState::FinWait1 => "FIN_WAIT1",
+            TcpState::FinWait2 => "FIN_WAIT2",
+            TcpState::TimeWait => "TIME_WAIT",
+            TcpState::Close => "CLOSE",
+            TcpState::CloseWait => "CLOSE_WAIT",
+            TcpState::LastAck => "LAST_ACK",
+            TcpState::Listen => "LISTEN",
+            TcpState::Closing => "CLOSING",
+            TcpState::NewSynRecv => "NEW_SYN_RECV",
+        }
+    }
+}
+
+impl fmt::Display for TcpState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Session {
+    pub user: String,
+    pub tty: String,
+    /// Remote host, `None` for local logins.
+    pub host: Option<String>,
+    pub login_time: DateTime<Utc>,
+    /// Time since the terminal was last used, `None` when it is not a device.
+    pub idle_seconds: Option<u64>,
+    pub pid: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct LoginRecord {
+    pub user: String,
+    pub tty: String,
+    pub host: Option<String>,
+    pub login_time: DateTime<Utc>,
+    /// Logout or the reboot that ended the session; `None` while it is still open.
+    pub logout_time: Option<DateTime<Utc>>,
+}
+
+/// Failed login attempts recorded in btmp within a time window.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct FailedLogins {
+    pub window_seconds: u64,
+    /// Attempts from one source at which it counts as brute-forcing.
+    pub brute_force_threshold: usize,
+    pub attempts: usize,
+    /// Most attempts first.
+    pub by_source: Vec<FailedLoginSource>,
+    /// Most attempts first.
+    pub by_user: Vec<FailedLoginUser>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct FailedLoginSource {
+    /// Remote host or address, `local` for console logins.
+    pub source: String,
+    pub attempts: usize,
+    /// Number of distinct user names tried.
+    pub users: usize,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// At least `brute_force_threshold` attempts within the window.
+    pub brute_force: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct FailedLoginUser {
+    pub user: String,
+    pub attempts: usize,
+    /// Number of distinct sources trying this user name.
+    pub sources: usize,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// sshd events from the authentication log within a time window.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SshActivity {
+    pub window_seconds: u64,
+    /// Log files read, including rotations.
+    pub files: Vec<String>,
+    /// Log files that could not be read.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    pub accepted: usize,
+    /// Failed attempts for existing users.
+    pub failed: usize,
+    /// Attempts for user names that do not exist: every password tried, or
+    /// the connection when none was. Not counted again under `failed`.
+    pub invalid_users: usize,
+    pub disconnects: usize,
+    /// Most failures first.
+    pub by_user: Vec<SshSummary>,
+    /// Most failures first.
+    pub by_source: Vec<SshSummary>,
+}
+
+/// Logins of one user name or from one source address.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SshSummary {
+    pub name: String,
+    pub accepted: usize,
+    pub failed: usize,
+    /// Attempts for user names that do not exist.
+    pub invalid_user: usize,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Pressure Stall Information: the share of time tasks were stalled waiting
+/// for CPU, memory or I/O.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct PressureInfo {
+    /// System-wide values from `/proc/pressure`, `None` when the kernel was
+    /// built without PSI or booted with `psi=0`.
+    pub system: Option<ResourcePressure>,
+    /// The cgroup server-stats runs in and the configured ones, from their
+    /// `*.pressure` files on the cgroup v2 hierarchy.
+    pub cgroups: Vec<CgroupPressure>,
+}
+
+impl PressureInfo {
+    /// `("system", ...)` followed by each cgroup path.
+    pub fn scopes(&self) -> impl Iterator<Item = (&str, &ResourcePressure)> {
+        let system = self.system.iter().map(|system| ("system", system));
+        system.chain(self.cgroups.iter().map(|cgroup| (cgroup.path.as_str(), &cgroup.pressure)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct CgroupPressure {
+    /// Path below the cgroup v2 mount point, `/` for its root.
+    pub path: String,
+    pub pressure: ResourcePressure,
+}
+
+/// Each resource is `None` when its pressure file is missing.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct ResourcePressure {
+    pub cpu: Option<PressureStats>,
+    pub memory: Option<PressureStats>,
+    pub io: Option<PressureStats>,
+}
+
+impl ResourcePressure {
+    /// The resources whose pressure file exists, by name.
+    pub fn resources(&self) -> impl Iterator<Item = (&'static str, &PressureStats)> {
+        [("cpu", &self.cpu), ("memory", &self.memory), ("io", &self.io)]
+            .into_iter()
+            .filter_map(|(name, stats)| Some((name, stats.as_ref()?)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[non_exhaustive]
+pub struct PressureStats {
+    /// At least one task was stalled.
+    pub some: StallTime,
+    /// All non-idle tasks were stalled at once. Older kernels do not report
+    /// it for CPU.
+    pub full: Option<StallTime>,
+}
+
+/// One `some` or `full` line of a pressure file.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[non_exhaustive]
+pub struct StallTime {
+    /// Percentage of the last 10 seconds spent stalled.
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    /// Stall time since boot, or since the cgroup was created.
+    pub total_microseconds: u64,
+}
+
+/// Change between two consecutive reports, used by watch mode.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct ReportDelta {
+    pub elapsed_seconds: f64,
+    /// Difference in percentage points.
+    pub cpu_usage_percent: Option<f32>,
+    pub memory_used_bytes: Option<i64>,
+    pub disks: Vec<DiskDelta>,
+    pub interfaces: Vec<InterfaceDelta>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct DiskDelta {
+    pub mount_point: String,
+    pub used_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct InterfaceDelta {
+    pub name: String,
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+}
+
+impl ReportDelta {
+    /// Compares `current` against `previous`. Disks and interfaces that only
+    /// appear in one of the two reports are left out.
+    pub fn between(previous: &Report, current: &Report) -> Self {
+        let elapsed = current.generated_at - previous.generated_at;
+
+        let previous_disks = previous.disks.as_deref().unwrap_or_default();
+        let disks = current
+            .disks
+            .iter()
+            .flatten()
+            .filter_map(|disk| {
+                let before = previous_disks.iter().find(|d| d.mount_point == disk.mount_point)?;
+                Some(DiskDelta {
+                    mount_point: disk.mount_point.clone(),
+                    used_bytes: signed_diff(before.used_bytes, disk.used_bytes),
+                })
+            })
+            .collect();
+
+        let previous_interfaces = previous.network.as_ref().map_or(&[][..], |network| &network.interfaces);
+        let interfaces = current
+            .network
+            .iter()
+            .flat_map(|network| &network.interfaces)
+            .filter_map(|interface| {
+                let before = previous_interfaces.iter().find(|i| i.name == interface.name)?;
+                Some(InterfaceDelta {
+                    name: interface.name.clone(),
+                    // Counters can reset when an interface is recreated.
+                    received_bytes: interface.received_bytes.saturating_sub(before.received_bytes),
+                    transmitted_bytes: interface.transmitted_bytes.saturating_sub(before.transmitted_bytes),
+                })
+            })
+            .collect();
+
+        ReportDelta {
+            elapsed_seconds: elapsed.num_milliseconds() as f64 / 1000.0,
+            cpu_usage_percent: current
+                .cpu
+                .as_ref()
+                .zip(previous.cpu.as_ref())
+                .map(|(current, previous)| current.usage_percent - previous.usage_percent),
+            memory_used_bytes: current
+                .memory
+                .as_ref()
+                .zip(previous.memory.as_ref())
+                .map(|(current, previous)| signed_diff(previous.used_bytes, current.used_bytes)),
+            disks,
+            interfaces,
+        }
+    }
+}
+
+fn signed_diff(before: u64, after: u64) -> i64 {
+    after as i64 - before as i64
+}
@@ -0,0 +1,60 @@
+use std::error::Error;
+
+use tiny_http::{Header, Method, Response, Server};
+
+use server_stats::alert::AlertEngine;
+use server_stats::collect::Sampler;
+use server_stats::config::Config;
+use server_stats::render::prometheus;
+use server_stats::report::Section;
+
+const EXPORTED_SECTIONS: [Section; 11] = [
+    Section::Cpu,
+    Section::Memory,
+    Section::Disk,
+    Section::System,
+    Section::Network,
+    Section::Ports,
+    Section::Users,
+    Section::Ssh,
+    Section::Pressure,
+    Section::BlockDevices,
+    Section::Raid,
+];
+
+/// Serves `/metrics` in the Prometheus text format until the process is killed.
+pub fn serve(listen: &str, config: &Config, mut alerts: Option<AlertEngine>) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let server = Server::http(listen)?;
+    eprintln!("Serving metrics on http://{}/metrics", listen);
+
+    // Processes are not exported, so skip collecting them. The sampler is
+    // kept between scrapes, so rates cover the time since the previous one
+    // and disk forecasts build up history.
+    let mut sampler = Sampler::from_config(config).with_sections(&EXPORTED_SECTIONS);
+
+    for request in server.incoming_requests() {
+        let response = match (request.method(), request.url()) {
+            (Method::Get, "/metrics") => {
+                let mut report = sampler.sample();
+                if let Some(engine) = alerts.as_mut() {
+                    report.alerts = Some(engine.evaluate(&report));
+                }
+                Response::from_string(prometheus::encode(&report))
+                    .with_header(header("text/plain; version=0.0.4; charset=utf-8"))
+            }
+            (Method::Get, "/") => Response::from_string("server-stats exporter\nMetrics are served at /metrics\n")
+                .with_header(header("text/plain; charset=utf-8")),
+            _ => Response::from_string("Not Found\n").with_status_code(404),
+        };
+
+        if let Err(err) = request.respond(response) {
+            eprintln!("Failed to send response: {}", err);
+        }
+    }
+
+    Ok(())
+}
+
+fn header(content_type: &str) -> Header {
+    Header::from_bytes("Content-Type", content_type).expect("static header is valid")
+}
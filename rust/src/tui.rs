@@ -0,0 +1,462 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Clear, Gauge, LineGauge, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+use server_stats::collect::Sampler;
+use server_stats::render::Units;
+use server_stats::report::{ProcessInfo, Report, ReportDelta, Section};
+
+/// What the dashboard draws, whatever the configured sections are. The
+/// header needs `system`, the process list `top-cpu` / `top-memory`; of
+/// `disk` and `users` only usage and sessions are sampled.
+const DRAWN_SECTIONS: [Section; 8] = [
+    Section::Cpu,
+    Section::Memory,
+    Section::Disk,
+    Section::System,
+    Section::Network,
+    Section::Users,
+    Section::TopCpu,
+    Section::TopMemory,
+];
+
+/// Maximum number of core gauges stacked in one column.
+const CORES_PER_COLUMN: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Cpu,
+    Memory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Normal,
+    Filter,
+    Help,
+}
+
+struct App {
+    sampler: Sampler,
+    report: Report,
+    delta: Option<ReportDelta>,
+    per_core: Vec<f32>,
+    processes: Vec<ProcessInfo>,
+    sort: SortKey,
+    filter: String,
+    mode: Mode,
+    table: TableState,
+    units: Units,
+}
+
+impl App {
+    fn new(mut sampler: Sampler, units: Units) -> Self {
+        let report = sampler.sample();
+        let mut app = App {
+            per_core: sampler.per_core_usage(),
+            processes: sampler.processes(),
+            sampler,
+            report,
+            delta: None,
+            sort: SortKey::Cpu,
+            filter: String::new(),
+            mode: Mode::Normal,
+            table: TableState::default().with_selected(0),
+            units,
+        };
+        app.sort_processes();
+        app
+    }
+
+    fn refresh(&mut self) {
+        let report = self.sampler.sample();
+        self.delta = Some(ReportDelta::between(&self.report, &report));
+        self.report = report;
+        self.per_core = self.sampler.per_core_usage();
+        self.processes = self.sampler.processes();
+        self.sort_processes();
+    }
+
+    fn sort_processes(&mut self) {
+        match self.sort {
+            SortKey::Cpu => self.processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent)),
+            SortKey::Memory => self.processes.sort_by_key(|p| std::cmp::Reverse(p.memory_bytes)),
+        }
+    }
+
+    fn visible_processes(&self) -> Vec<&ProcessInfo> {
+        let filter = self.filter.to_lowercase();
+        self.processes
+            .iter()
+            .filter(|p| {
+                filter.is_empty()
+                    || p.name.to_lowercase().contains(&filter)
+                    || p.user.to_lowercase().contains(&filter)
+                    || p.pid.to_string().contains(&filter)
+            })
+            .collect()
+    }
+
+    /// Handles a key press, returning `false` when the app should exit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match self.mode {
+            Mode::Help => self.mode = Mode::Normal,
+            Mode::Filter => match key.code {
+                KeyCode::Enter => self.mode = Mode::Normal,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.mode = Mode::Normal;
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.table.select(Some(0));
+                }
+                _ => {}
+            },
+            Mode::Normal => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return false,
+                KeyCode::Char('?') | KeyCode::Char('h') => self.mode = Mode::Help,
+                KeyCode::Char('/') => self.mode = Mode::Filter,
+                KeyCode::Char('c') => {
+                    self.sort = SortKey::Cpu;
+                    self.sort_processes();
+                }
+                KeyCode::Char('m') => {
+                    self.sort = SortKey::Memory;
+                    self.sort_processes();
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+                KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+                KeyCode::PageDown => self.scroll(10),
+                KeyCode::PageUp => self.scroll(-10),
+                KeyCode::Home | KeyCode::Char('g') => self.table.select(Some(0)),
+                KeyCode::End | KeyCode::Char('G') => self.scroll(isize::MAX / 2),
+                _ => {}
+            },
+        }
+        true
+    }
+
+    fn scroll(&mut self, by: isize) {
+        let len = self.visible_processes().len();
+        if len == 0 {
+            self.table.select(None);
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let next = current.saturating_add(by).clamp(0, len as isize - 1);
+        self.table.select(Some(next as usize));
+    }
+}
+
+/// Runs the full-screen dashboard until the user quits, resampling every `interval`.
+pub fn run(sampler: Sampler, interval: Duration, units: Units) -> io::Result<()> {
+    let interval = interval.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    let app = App::new(sampler.with_sections(&DRAWN_SECTIONS).with_details(false), units);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, app, interval);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut app: App, interval: Duration) -> io::Result<()> {
+    let mut last_sample = Instant::now();
+
+    loop {
+        terminal.draw(|frame| draw(frame, &mut app))?;
+
+        let timeout = interval.saturating_sub(last_sample.elapsed());
+        if event::poll(timeout)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !app.handle_key(key)
+        {
+            return Ok(());
+        }
+
+        if last_sample.elapsed() >= interval {
+            app.refresh();
+            last_sample = Instant::now();
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let core_rows = app.per_core.len().clamp(1, CORES_PER_COLUMN) as u16;
+    let [header, cpu, memory, middle, processes, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(core_rows + 2),
+        Constraint::Length(3),
+        Constraint::Length(8),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    draw_header(frame, header, &app.report);
+    let average = app.report.cpu.as_ref().map_or(0.0, |cpu| cpu.usage_percent);
+    draw_cpu(frame, cpu, &app.per_core, average);
+    draw_memory(frame, memory, &app.report, app.units);
+
+    let [disks, network, users] = Layout::horizontal([
+        Constraint::Percentage(45),
+        Constraint::Percentage(35),
+        Constraint::Percentage(20),
+    ])
+    .areas(middle);
+    draw_disks(frame, disks, &app.report, app.units);
+    draw_network(frame, network, &app.report, app.delta.as_ref(), app.units);
+    draw_users(frame, users, &app.report);
+
+    draw_processes(frame, processes, app);
+    draw_footer(frame, footer, app);
+
+    if app.mode == Mode::Help {
+        draw_help(frame);
+    }
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, report: &Report) {
+    let Some(system) = &report.system else {
+        return;
+    };
+    let uptime = system.uptime_seconds;
+    let text = format!(
+        " {} | {} {} | up {}d {}h {}m | load {:.2} {:.2} {:.2}",
+        report.hostname.as_deref().unwrap_or("unknown"),
+        system.os_name.as_deref().unwrap_or("Unknown"),
+        system.os_version.as_deref().unwrap_or(""),
+        uptime / 86400,
+        (uptime % 86400) / 3600,
+        (uptime % 3600) / 60,
+        system.load_average.one,
+        system.load_average.five,
+        system.load_average.fifteen,
+    );
+    frame.render_widget(Paragraph::new(text).style(Style::new().add_modifier(Modifier::BOLD)), area);
+}
+
+fn draw_cpu(frame: &mut Frame, area: Rect, per_core: &[f32], average: f32) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" CPU {:.1}% ", average));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let columns = per_core.len().div_ceil(CORES_PER_COLUMN).max(1);
+    let column_areas = Layout::horizontal(vec![Constraint::Ratio(1, columns as u32); columns]).split(inner);
+
+    for (column, chunk) in per_core.chunks(CORES_PER_COLUMN).enumerate() {
+        let rows = Layout::vertical(vec![Constraint::Length(1); chunk.len()]).split(column_areas[column]);
+        for (row, usage) in chunk.iter().enumerate() {
+            let core = column * CORES_PER_COLUMN + row;
+            let gauge = LineGauge::default()
+                .filled_style(Style::new().fg(usage_color(*usage as f64)))
+                .label(format!("{:>3} {:>5.1}%", core, usage))
+                .ratio((*usage as f64 / 100.0).clamp(0.0, 1.0));
+            frame.render_widget(gauge, rows[row]);
+        }
+    }
+}
+
+fn draw_memory(frame: &mut Frame, area: Rect, report: &Report, units: Units) {
+    let Some(memory) = &report.memory else {
+        return;
+    };
+    let [mem_area, swap_area] = Layout::horizontal([Constraint::Percentage(50); 2]).areas(area);
+
+    let mem = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title(" Memory "))
+        .gauge_style(Style::new().fg(usage_color(memory.used_percent)))
+        .label(format!(
+            "{:.2} / {:.2} GB ({:.1}%)",
+            units.gb(memory.used_bytes),
+            units.gb(memory.total_bytes),
+            memory.used_percent
+        ))
+        .ratio((memory.used_percent / 100.0).clamp(0.0, 1.0));
+    frame.render_widget(mem, mem_area);
+
+    let swap_block = Block::default().borders(Borders::ALL).title(" Swap ");
+    match &memory.swap {
+        Some(swap) => {
+            let gauge = Gauge::default()
+                .block(swap_block)
+                .gauge_style(Style::new().fg(usage_color(swap.used_percent)))
+                .label(format!(
+                    "{:.2} / {:.2} GB ({:.1}%)",
+                    units.gb(swap.used_bytes),
+                    units.gb(swap.total_bytes),
+                    swap.used_percent
+                ))
+                .ratio((swap.used_percent / 100.0).clamp(0.0, 1.0));
+            frame.render_widget(gauge, swap_area);
+        }
+        None => frame.render_widget(Paragraph::new("Not configured").block(swap_block), swap_area),
+    }
+}
+
+fn draw_disks(frame: &mut Frame, area: Rect, report: &Report, units: Units) {
+    let rows = report.disks.iter().flatten().map(|disk| {
+        Row::new(vec![
+            Cell::from(disk.mount_point.clone()),
+            Cell::from(format!("{:.1}G", units.gb(disk.total_bytes))),
+            Cell::from(format!("{:.1}G", units.gb(disk.used_bytes))),
+            Cell::from(format!("{:.1}%", disk.used_percent))
+                .style(Style::new().fg(usage_color(disk.used_percent))),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [Constraint::Min(10), Constraint::Length(8), Constraint::Length(8), Constraint::Length(6)],
+    )
+    .header(Row::new(["Mounted on", "Size", "Used", "Use%"]).style(Style::new().add_modifier(Modifier::BOLD)))
+    .block(Block::default().borders(Borders::ALL).title(" Disks "));
+    frame.render_widget(table, area);
+}
+
+fn draw_network(frame: &mut Frame, area: Rect, report: &Report, delta: Option<&ReportDelta>, units: Units) {
+    let rows = report.network.iter().flat_map(|network| &network.interfaces).map(|interface| {
+        let rates = delta
+            .and_then(|delta| {
+                let elapsed = delta.elapsed_seconds.max(f64::EPSILON);
+                delta.interfaces.iter().find(|i| i.name == interface.name).map(|i| {
+                    (
+                        format!("{:.1} KB/s", units.kb(i.received_bytes as f64) / elapsed),
+                        format!("{:.1} KB/s", units.kb(i.transmitted_bytes as f64) / elapsed),
+                    )
+                })
+            })
+            .unwrap_or_else(|| ("-".to_string(), "-".to_string()));
+        Row::new(vec![interface.name.clone(), rates.0, rates.1])
+    });
+    let title = match report.network.as_ref().and_then(|network| network.listening_ports) {
+        Some(ports) => format!(" Network ({} listening) ", ports),
+        None => " Network ".to_string(),
+    };
+    let table = Table::new(rows, [Constraint::Min(6), Constraint::Length(12), Constraint::Length(12)])
+        .header(Row::new(["Interface", "RX", "TX"]).style(Style::new().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(table, area);
+}
+
+fn draw_users(frame: &mut Frame, area: Rect, report: &Report) {
+    let logged_in = report.users.as_ref().and_then(|users| users.logged_in.as_ref());
+    let lines: Vec<Line> = match logged_in {
+        Some(users) if users.is_empty() => vec![Line::from("No users logged in")],
+        Some(users) => users
+            .iter()
+            .map(|session| Line::from(format!("{} {}", session.user, session.tty)))
+            .collect(),
+        None => vec![Line::from("Unavailable")],
+    };
+    let count = logged_in.map_or(0, Vec::len);
+    let paragraph = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(" Users ({}) ", count)));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_processes(frame: &mut Frame, area: Rect, app: &mut App) {
+    let units = app.units;
+    let processes = app.visible_processes();
+    let count = processes.len();
+    let rows: Vec<Row> = processes
+        .iter()
+        .map(|process| {
+            Row::new(vec![
+                process.pid.to_string(),
+                process.user.clone(),
+                format!("{:.1}", process.cpu_percent),
+                format!("{:.1}", process.memory_percent),
+                format!("{:.1}M", units.mb(process.memory_bytes as f64)),
+                process.name.clone(),
+            ])
+        })
+        .collect();
+
+    let sort = match app.sort {
+        SortKey::Cpu => "CPU",
+        SortKey::Memory => "memory",
+    };
+    let title = if app.filter.is_empty() {
+        format!(" Processes ({}) sorted by {} ", count, sort)
+    } else {
+        format!(" Processes ({}) sorted by {}, filter \"{}\" ", count, sort, app.filter)
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(12),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Min(10),
+        ],
+    )
+    .header(Row::new(["PID", "USER", "CPU%", "MEM%", "MEMORY", "COMMAND"]).style(Style::new().add_modifier(Modifier::BOLD)))
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+    .block(Block::default().borders(Borders::ALL).title(title));
+
+    if app.table.selected().is_some_and(|selected| selected >= count) {
+        app.table.select(count.checked_sub(1));
+    }
+    frame.render_stateful_widget(table, area, &mut app.table);
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let text = match app.mode {
+        Mode::Filter => format!(" Filter: {}_  (Enter to apply, Esc to clear)", app.filter),
+        _ => " q quit  ? help  c sort by CPU  m sort by memory  / filter  ↑↓ scroll".to_string(),
+    };
+    frame.render_widget(Paragraph::new(text).style(Style::new().fg(Color::Black).bg(Color::Gray)), area);
+}
+
+fn draw_help(frame: &mut Frame) {
+    let lines = vec![
+        Line::from("q, Esc        quit"),
+        Line::from("?, h          toggle this help"),
+        Line::from("c             sort processes by CPU"),
+        Line::from("m             sort processes by memory"),
+        Line::from("/             filter by name, user or PID"),
+        Line::from("↑/k ↓/j       move selection"),
+        Line::from("PgUp PgDn     move selection by 10"),
+        Line::from("Home/g End/G  jump to first / last"),
+        Line::from(""),
+        Line::from("Press any key to close"),
+    ];
+    let area = centered(frame.area(), 48, lines.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Help ")), area);
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn usage_color(percent: f64) -> Color {
+    if percent >= 90.0 {
+        Color::Red
+    } else if percent >= 70.0 {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
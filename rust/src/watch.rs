@@ -0,0 +1,34 @@
+use std::io::Write;
+use std::time::Duration;
+
+use server_stats::alert::AlertEngine;
+use server_stats::collect::Sampler;
+use server_stats::render::{self, Format, TextOptions};
+use server_stats::report::ReportDelta;
+
+/// Samples the machine every `interval` until interrupted, reusing the same
+/// sysinfo state so CPU usage covers the whole interval.
+pub fn watch(
+    mut sampler: Sampler,
+    interval: Duration,
+    format: Format,
+    options: &TextOptions,
+    mut alerts: Option<AlertEngine>,
+) {
+    let interval = interval.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    let mut previous = None;
+
+    loop {
+        let mut report = sampler.sample();
+        if let Some(engine) = alerts.as_mut() {
+            report.alerts = Some(engine.evaluate(&report));
+        }
+        let delta = previous.as_ref().map(|previous| ReportDelta::between(previous, &report));
+
+        render::render_watch(&report, delta.as_ref(), format, options);
+        let _ = std::io::stdout().flush();
+
+        previous = Some(report);
+        std::thread::sleep(interval);
+    }
+}